@@ -16,6 +16,9 @@ use crate::window::WindowManager;
 #[cfg(feature = "cocoa")]
 pub use alchemy_cocoa::app::{App as PlatformAppBridge};
 
+#[cfg(feature = "headless")]
+pub use alchemy_headless::app::{App as PlatformAppBridge};
+
 /// A default delegate that is mostly used for creating the initial struct,
 /// without requiring the actual `AppDelegate` from the user. Will ideally
 /// never see the light of day.
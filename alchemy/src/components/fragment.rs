@@ -12,7 +12,7 @@ pub struct FragmentProps;
 /// Fragments are special - you can do something like the following in cases where you
 /// want to render some views without requiring an intermediate view.
 ///
-/// ```
+/// ```rust,ignore
 /// <Fragment>
 ///     <View />
 ///     <View />
@@ -29,7 +29,7 @@ impl Fragment {
 }
 
 impl Props for Fragment {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, _: &std::any::Any) {}
 }
 
 impl Component for Fragment {
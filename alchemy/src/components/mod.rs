@@ -4,9 +4,37 @@
 //! functioning app.
 
 pub mod fragment;
+pub mod portal;
 pub mod view;
 pub mod text;
 
 pub use fragment::Fragment;
+pub use portal::Portal;
 pub use view::View;
 pub use text::Text;
+
+use alchemy_lifecycle::accessibility::{AccessibilityProperties, AccessibilityRole};
+
+/// Builds `AccessibilityProperties` from the string props that core Components take, where an
+/// empty string means "not set". Returns `None` if nothing was set, and the role is still
+/// `default_role`, so that plain layout nodes are left out of the accessibility tree.
+pub(crate) fn accessibility_properties(
+    role: AccessibilityRole,
+    default_role: AccessibilityRole,
+    label: &str,
+    hint: &str,
+    value: &str
+) -> Option<AccessibilityProperties> {
+    if role == default_role && label.is_empty() && hint.is_empty() && value.is_empty() {
+        return None;
+    }
+
+    let non_empty = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+
+    Some(AccessibilityProperties {
+        role,
+        label: non_empty(label),
+        hint: non_empty(hint),
+        value: non_empty(value)
+    })
+}
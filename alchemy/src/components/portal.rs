@@ -0,0 +1,88 @@
+//! A Portal renders its children into a different root than the one it's declared in - e.g,
+//! the root view of a `Window` (possibly another one). This is useful for overlays, like
+//! tooltips, popovers and dropdown menus, that need to be declared inside the component that
+//! owns them, but laid out on top of everything else.
+
+use alchemy_lifecycle::ComponentKey;
+use alchemy_lifecycle::error::Error;
+use alchemy_lifecycle::rsx::RSX;
+use alchemy_lifecycle::traits::{Component, Props};
+
+use crate::components::Fragment;
+
+#[derive(Default)]
+pub struct PortalProps {
+    pub target: Option<ComponentKey>
+}
+
+/// Portals lay out and attach their children under `target`, which should be a root
+/// `ComponentKey` (e.g, from `Window::render_key()`) registered with the same `RenderEngine`.
+/// Their children are still treated as descendants of the Portal for lifecycle, context and
+/// error boundary purposes. For example, to render a tooltip into an overlay window:
+///
+/// ```rust,no_run
+/// # use alchemy::{rsx, ComponentKey, Error, Portal, RSX, View, Window, WindowDelegate};
+/// struct Overlay;
+///
+/// impl WindowDelegate for Overlay {}
+///
+/// struct Main {
+///     overlay: ComponentKey
+/// }
+///
+/// impl WindowDelegate for Main {
+///     fn render(&self) -> Result<RSX, Error> {
+///         let window_key = self.overlay;
+///
+///         Ok(rsx! {
+///             <Portal target={Some(window_key)}>
+///                 <View styles=["tooltip"] />
+///             </Portal>
+///         })
+///     }
+/// }
+///
+/// let overlay = Window::new(Overlay);
+/// let main = Window::with_render_engine(Main { overlay: overlay.render_key() }, overlay.render_engine());
+/// ```
+///
+/// Each `Window` gets a `RenderEngine` of its own by default, so both need to share one (via
+/// `Window::with_render_engine()`, as above) for the Portal to reach the other.
+///
+/// If no `target` is given, children are attached in place, as though the Portal weren't there.
+/// A `target` in another `RenderEngine` (or one that doesn't exist) is an error, handled like
+/// the Portal failing to render.
+#[derive(Default)]
+pub struct Portal {
+    target: Option<ComponentKey>
+}
+
+impl Portal {
+    pub fn default_props() -> PortalProps {
+        PortalProps::default()
+    }
+}
+
+impl Props for Portal {
+    fn set_props(&mut self, new_props: &std::any::Any) {
+        if let Some(props) = new_props.downcast_ref::<PortalProps>() {
+            self.target = props.target;
+        }
+    }
+}
+
+impl Component for Portal {
+    fn new(_: ComponentKey) -> Portal {
+        Portal::default()
+    }
+
+    fn portal_target(&self) -> Option<ComponentKey> {
+        self.target
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(RSX::node("Fragment", "".into(), |key| {
+            Box::new(<Fragment as Component>::new(key))
+        }, PortalProps::default(), children))
+    }
+}
@@ -5,30 +5,57 @@
 
 use std::sync::{Mutex};
 
-use alchemy_styles::styles::{Appearance, Layout};
+use alchemy_styles::styles::{Appearance, Layout, MeasureFunc};
 
 use alchemy_lifecycle::ComponentKey;
+use alchemy_lifecycle::accessibility::{AccessibilityProperties, AccessibilityRole};
 use alchemy_lifecycle::error::Error;
 use alchemy_lifecycle::rsx::RSX;
 use alchemy_lifecycle::traits::{Component, Props, PlatformSpecificNodeType};
 
+use crate::components::accessibility_properties;
+
 #[cfg(feature = "cocoa")]
 use alchemy_cocoa::text::{Text as PlatformTextBridge};
 
-pub struct TextProps;
+#[cfg(feature = "headless")]
+use alchemy_headless::text::{Text as PlatformTextBridge};
+
+pub struct TextProps {
+    pub role: AccessibilityRole,
+    pub label: String,
+    pub hint: String,
+    pub value: String
+}
+
+impl Default for TextProps {
+    fn default() -> TextProps {
+        TextProps {
+            role: AccessibilityRole::Text,
+            label: String::new(),
+            hint: String::new(),
+            value: String::new()
+        }
+    }
+}
 
 /// Text rendering is a complicated mess, and being able to defer to the
 /// backing platform for this is amazing. This is a very common Component.
 ///
-/// Views accept styles and event callbacks as props. For example:
+/// Views accept styles and event callbacks as props, along with accessibility props (`role`,
+/// `label`, `hint` and `value`). Without a `label`, the text itself is read out. Like Views, Text
+/// is only exposed to assistive technology once one of those is set. For example:
 ///
+/// ```rust,ignore
+/// <Text styles=["styleKey1", "styleKey2"] role="heading" />
 /// ```
-/// <Text styles=["styleKey1", "styleKey2"] />
-/// ```
-pub struct Text(Mutex<PlatformTextBridge>);
+pub struct Text {
+    bridge: Mutex<PlatformTextBridge>,
+    accessibility: Option<AccessibilityProperties>
+}
 
 impl Text {
-    pub fn default_props() -> TextProps { TextProps {} }
+    pub fn default_props() -> TextProps { TextProps::default() }
     // This is very naive for now, but it's fine - we probably
     // want to do some fun stuff here later with stylized text
     // rendering anyway.
@@ -38,18 +65,31 @@ impl Text {
 }
 
 impl Props for Text {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, new_props: &std::any::Any) {
+        if let Some(props) = new_props.downcast_ref::<TextProps>() {
+            self.accessibility = accessibility_properties(
+                props.role,
+                AccessibilityRole::Text,
+                &props.label,
+                &props.hint,
+                &props.value
+            );
+        }
+    }
 }
 
 impl Component for Text {
     fn new(_: ComponentKey) -> Text {
-        Text(Mutex::new(PlatformTextBridge::new()))
+        Text {
+            bridge: Mutex::new(PlatformTextBridge::new()),
+            accessibility: None
+        }
     }
 
     fn has_native_backing_node(&self) -> bool { true }
     
     fn borrow_native_backing_node(&self) -> Option<PlatformSpecificNodeType> {
-        let bridge = self.0.lock().unwrap();
+        let bridge = self.bridge.lock().unwrap();
         Some(bridge.borrow_native_backing_node())
     }
 
@@ -57,26 +97,41 @@ impl Component for Text {
     // Panic might not be right here, but eh, should probably do something.
     //fn append_child_component(&self, _component: &Component) {}
 
+    fn accessibility_properties(&self) -> Option<AccessibilityProperties> {
+        self.accessibility.clone()
+    }
+
+    fn apply_accessibility(&self, properties: &AccessibilityProperties) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.set_accessibility(properties);
+    }
+
+    fn measure_func(&self, appearance: &Appearance) -> Option<MeasureFunc> {
+        let bridge = self.bridge.lock().unwrap();
+        Some(bridge.measure_func(appearance))
+    }
+
     fn apply_styles(&self, appearance: &Appearance, layout: &Layout) {
-        let mut bridge = self.0.lock().unwrap();
+        let mut bridge = self.bridge.lock().unwrap();
         bridge.apply_styles(appearance, layout);
     }
 
-    fn component_did_mount(&mut self) {
-        let mut bridge = self.0.lock().unwrap();
+    fn apply_text(&self, text: &str) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.set_text(text.to_string());
         bridge.render();
     }
 
     // This one is a bit tricky, due to the way we have to do props + children in Rust.
-    // Here, we set it as the new text on render(), and then ensure it gets rendered on
-    // `component_did_update()` and `component_did_mount()`.
+    // Here, we hold on to the new text on render() so that it can be measured; it's shown
+    // once the reconciler commits it, through `apply_text()`.
     fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
         let text = children.iter().map(|child| match child {
             RSX::VirtualText(s) => s.0.to_owned(),
             _ => String::new()
         }).collect::<String>();
         
-        let mut bridge = self.0.lock().unwrap();
+        let mut bridge = self.bridge.lock().unwrap();
         bridge.set_text(text);
         
         Ok(RSX::None)
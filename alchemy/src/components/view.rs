@@ -8,45 +8,79 @@ use std::sync::Mutex;
 use alchemy_styles::{Appearance, Layout};
 
 use alchemy_lifecycle::ComponentKey;
+use alchemy_lifecycle::accessibility::{AccessibilityProperties, AccessibilityRole};
 use alchemy_lifecycle::error::Error;
 use alchemy_lifecycle::rsx::RSX;
 use alchemy_lifecycle::traits::{Component, Props, PlatformSpecificNodeType};
 
-use crate::components::Fragment;
+use crate::components::{accessibility_properties, Fragment};
 
 #[cfg(feature = "cocoa")]
 use alchemy_cocoa::view::{View as PlatformViewBridge};
 
-pub struct ViewProps;
+#[cfg(feature = "headless")]
+use alchemy_headless::view::{View as PlatformViewBridge};
+
+pub struct ViewProps {
+    pub role: AccessibilityRole,
+    pub label: String,
+    pub hint: String,
+    pub value: String
+}
+
+impl Default for ViewProps {
+    fn default() -> ViewProps {
+        ViewProps {
+            role: AccessibilityRole::Group,
+            label: String::new(),
+            hint: String::new(),
+            value: String::new()
+        }
+    }
+}
 
 /// Views are the most basic piece of the API. If you want to display something, you'll
 /// probably be reaching for a View first and foremost.
 ///
-/// Views accept styles and event callbacks as props. For example:
+/// Views accept styles and event callbacks as props, along with accessibility props (`role`,
+/// `label`, `hint` and `value`). Views are groups unless told otherwise, and are only exposed to
+/// assistive technology once one of those is set (or they can take focus). For example:
 ///
-/// ```
-/// <View styles=["styleKey1", "styleKey2"] />
+/// ```rust,ignore
+/// <View styles=["styleKey1", "styleKey2"] role="button" label="Save" />
 /// ```
 pub struct View {
-    bridge: Mutex<PlatformViewBridge>
+    bridge: Mutex<PlatformViewBridge>,
+    accessibility: Option<AccessibilityProperties>
 }
 
 impl Default for View {
     fn default() -> View {
         View {
-            bridge: Mutex::new(PlatformViewBridge::new())
+            bridge: Mutex::new(PlatformViewBridge::new()),
+            accessibility: None
         }
     }
 }
 
 impl View {
     pub fn default_props() -> ViewProps {
-        ViewProps {}
+        ViewProps::default()
     }
 }
 
 impl Props for View {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, new_props: &std::any::Any) {
+        if let Some(props) = new_props.downcast_ref::<ViewProps>() {
+            self.accessibility = accessibility_properties(
+                props.role,
+                AccessibilityRole::Group,
+                &props.label,
+                &props.hint,
+                &props.value
+            );
+        }
+    }
 }
 
 impl Component for View {
@@ -66,6 +100,30 @@ impl Component for View {
         bridge.append_child(node);
     }
 
+    fn insert_child_node_at(&self, node: PlatformSpecificNodeType, index: usize) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.insert_child(node, index);
+    }
+
+    fn remove_child_node(&self, node: PlatformSpecificNodeType) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.remove_child(node);
+    }
+
+    fn replace_child_node(&self, old: PlatformSpecificNodeType, new: PlatformSpecificNodeType) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.replace_child(old, new);
+    }
+
+    fn accessibility_properties(&self) -> Option<AccessibilityProperties> {
+        self.accessibility.clone()
+    }
+
+    fn apply_accessibility(&self, properties: &AccessibilityProperties) {
+        let mut bridge = self.bridge.lock().unwrap();
+        bridge.set_accessibility(properties);
+    }
+
     fn apply_styles(&self, appearance: &Appearance, layout: &Layout) {
         let mut bridge = self.bridge.lock().unwrap();
         bridge.apply_styles(appearance, layout);
@@ -74,6 +132,6 @@ impl Component for View {
     fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
         Ok(RSX::node("Fragment", "".into(), |key| {
             Box::new(<Fragment as Component>::new(key))
-        }, Box::new(ViewProps {}), children))
+        }, ViewProps::default(), children))
     }
 }
@@ -0,0 +1,134 @@
+//! Renders `RSX` trees to static HTML. The tree is mounted into a `RenderEngine` of its own,
+//! styled through the `THEME_ENGINE` and laid out just as it would be in a `Window`; every
+//! natively backed `Component` is then written out as an absolutely positioned `<div>`, with its
+//! `Appearance` as inline CSS.
+//!
+//! This is handy for snapshot testing screens (e.g, in CI, with the `headless` feature), and for
+//! publishing static previews of an app.
+
+use std::error::Error;
+use std::fmt::Write;
+
+use htmlescape::encode_minimal;
+
+use alchemy_lifecycle::RenderEngine;
+use alchemy_lifecycle::inspector::InspectorNode;
+use alchemy_lifecycle::rsx::RSX;
+use alchemy_styles::styles::{Color, FontStyle, FontWeight, Layout, TextAlignment};
+
+use crate::components::View;
+
+/// Mounts `tree` in a root of `width` by `height`, and returns it as HTML. Nothing here is
+/// tied to a `Window`, so styles need to have been registered beforehand (e.g, through
+/// `shared_app().register_styles()`).
+pub fn render_to_html(tree: RSX, width: f64, height: f64) -> Result<String, Box<Error>> {
+    let render_engine = RenderEngine::new();
+    let key = render_engine.register_root_component(View::default())?;
+    render_engine.diff_and_render_root(key, (width, height), tree)?;
+
+    let mut html = String::new();
+    write_node(&mut html, &render_engine.inspect(key)?, 0)?;
+    Ok(html)
+}
+
+/// Writes `node` (if it's natively backed) and its children. Anything that isn't natively backed
+/// doesn't exist as far as layout is concerned, so its children are written in its place.
+fn write_node(html: &mut String, node: &InspectorNode, depth: usize) -> Result<(), Box<Error>> {
+    let layout = match (node.is_native_backed, &node.layout) {
+        (true, Some(layout)) => layout,
+
+        _ => {
+            for child in &node.children {
+                write_node(html, child, depth)?;
+            }
+
+            return Ok(());
+        }
+    };
+
+    let indent = "  ".repeat(depth);
+    write!(html, "{}<div data-tag=\"{}\"", indent, encode_minimal(node.tag))?;
+
+    if !node.style_keys.is_empty() {
+        write!(html, " class=\"{}\"", encode_minimal(&node.style_keys.join(" ")))?;
+    }
+
+    write!(html, " style=\"{}\">", encode_minimal(&css_for(node, layout, depth == 0)))?;
+
+    if let Some(text) = &node.text {
+        html.push_str(&encode_minimal(text));
+    }
+
+    if !node.children.is_empty() {
+        html.push('\n');
+
+        for child in &node.children {
+            write_node(html, child, depth + 1)?;
+        }
+
+        html.push_str(&indent);
+    }
+
+    writeln!(html, "</div>")?;
+    Ok(())
+}
+
+/// Converts the computed `Layout` and `Appearance` of `node` into inline CSS. Layouts are
+/// relative to the parent node, which maps onto absolute positioning; the root is positioned
+/// relatively, so the output can be dropped into a page as-is. Font styles are only written out
+/// for nodes that have text, to keep the output readable.
+fn css_for(node: &InspectorNode, layout: &Layout, is_root: bool) -> String {
+    let appearance = &node.appearance;
+    let mut css = format!(
+        "position: {}; left: {}px; top: {}px; width: {}px; height: {}px;",
+        if is_root { "relative" } else { "absolute" },
+        layout.location.x,
+        layout.location.y,
+        layout.size.width,
+        layout.size.height
+    );
+
+    if appearance.background_color.alpha > 0 {
+        css.push_str(&format!(" background-color: {};", css_color(&appearance.background_color)));
+    }
+
+    if appearance.opacity < 1. {
+        css.push_str(&format!(" opacity: {};", appearance.opacity));
+    }
+
+    if node.text.is_none() {
+        return css;
+    }
+
+    if appearance.text_color.alpha > 0 {
+        css.push_str(&format!(" color: {};", css_color(&appearance.text_color)));
+    }
+
+    css.push_str(&format!(" font-size: {}px;", appearance.font_size));
+
+    match appearance.font_style {
+        FontStyle::Normal => {},
+        FontStyle::Italic => { css.push_str(" font-style: italic;"); },
+        FontStyle::Oblique => { css.push_str(" font-style: oblique;"); }
+    }
+
+    if appearance.font_weight == FontWeight::Bold {
+        css.push_str(" font-weight: bold;");
+    }
+
+    match appearance.text_alignment {
+        TextAlignment::Auto => {},
+        TextAlignment::Left => { css.push_str(" text-align: left;"); },
+        TextAlignment::Right => { css.push_str(" text-align: right;"); },
+        TextAlignment::Center => { css.push_str(" text-align: center;"); },
+        TextAlignment::Justify => { css.push_str(" text-align: justify;"); }
+    }
+
+    css
+}
+
+/// Converts a `Color` into a CSS `rgba()` value, with alpha rounded to two decimal places.
+fn css_color(color: &Color) -> String {
+    let alpha = (f32::from(color.alpha) / 255. * 100.).round() / 100.;
+    format!("rgba({}, {}, {}, {})", color.red, color.green, color.blue, alpha)
+}
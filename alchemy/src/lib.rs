@@ -9,12 +9,18 @@ use std::sync::Arc;
 pub use lazy_static::lazy_static;
 use proc_macro_hack::proc_macro_hack;
 
-pub use alchemy_lifecycle::{ComponentKey, text};
+pub use alchemy_lifecycle::{ComponentKey, Patch, RenderEngine, RENDER_ENGINE, text};
 pub use alchemy_lifecycle::traits::{
     AppDelegate, Component, Props as ComponentProps, WindowDelegate
 };
 
-pub use alchemy_lifecycle::error::Error;
+pub use alchemy_lifecycle::accessibility::{AccessibilityNode, AccessibilityProperties, AccessibilityRole};
+pub use alchemy_lifecycle::context::Context;
+pub use alchemy_lifecycle::error::{Error, ErrorInfo};
+pub use alchemy_lifecycle::events::{Event, KeyboardEvent, Modifiers, MouseButton, MouseEvent};
+pub use alchemy_lifecycle::inspector::InspectorNode;
+pub use alchemy_lifecycle::recording::{Divergence, Recording};
+pub use alchemy_lifecycle::refs::Ref;
 pub use alchemy_lifecycle::rsx::{
     RSX, VirtualNode, VirtualText
 };
@@ -32,7 +38,12 @@ mod app;
 use app::App;
 
 pub mod components;
-pub use components::{Fragment, Text, View};
+pub use components::{Fragment, Portal, Text, View};
+
+pub mod html;
+pub use html::render_to_html;
+
+pub mod remote;
 
 pub mod window;
 pub use window::Window;
@@ -0,0 +1,28 @@
+//! Mirrors `Component` trees that live in another process (e.g, a plugin host, or a long-running
+//! engine), with the `Component`s on the other end streaming their commits over a `Connection`.
+//! See `alchemy_lifecycle::remote` for how the protocol works, and for the other end.
+
+use std::sync::Arc;
+
+use alchemy_lifecycle::ComponentKey;
+use alchemy_lifecycle::traits::Component;
+
+pub use alchemy_lifecycle::remote::{
+    dispatch_events, stream_commits, Connection, Message, Mirror, NodeId, Operation
+};
+
+use crate::components::{Text, View};
+
+/// Creates a `Mirror` that attaches the remote tree under `root` (e.g, `window.render_key()`).
+/// `Text` nodes are mirrored as `Text`s, and anything else as a `View`. Call `run()` on it
+/// (typically, on a thread of its own) to start applying what comes in over `connection`.
+pub fn mirror(root: ComponentKey, connection: Arc<Connection>) -> Arc<Mirror> {
+    Mirror::new(root, connection, Box::new(|kind| {
+        let component: Box<Component> = match kind {
+            "Text" => Box::new(Text::new(ComponentKey::placeholder())),
+            _ => Box::new(View::new(ComponentKey::placeholder()))
+        };
+
+        Some(component)
+    }))
+}
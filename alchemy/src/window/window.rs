@@ -3,9 +3,9 @@
 
 use std::sync::{Arc, Mutex};
 
-use alchemy_lifecycle::{ComponentKey, RENDER_ENGINE};
+use alchemy_lifecycle::{ComponentKey, RenderEngine};
 use alchemy_lifecycle::rsx::RSX;
-use alchemy_lifecycle::traits::{Component, WindowDelegate};
+use alchemy_lifecycle::traits::{Component, PlatformSpecificNodeType, WindowDelegate};
 
 use alchemy_styles::{Appearance, Style, StylesList, THEME_ENGINE};
 
@@ -15,6 +15,9 @@ use crate::components::View;
 #[cfg(feature = "cocoa")]
 use alchemy_cocoa::window::{Window as PlatformWindowBridge};
 
+#[cfg(feature = "headless")]
+use alchemy_headless::window::{Window as PlatformWindowBridge};
+
 /// AppWindow contains the inner details of a Window. It's guarded by a Mutex on `Window`,
 /// and you shouldn't create this yourself, but it's documented here so you can understand what
 /// it holds.
@@ -25,6 +28,7 @@ pub struct AppWindow {
     pub dimensions: (f64, f64, f64, f64),
     pub bridge: PlatformWindowBridge,
     pub delegate: Box<WindowDelegate>,
+    pub render_engine: Arc<RenderEngine>,
     pub render_key: ComponentKey
 }
 
@@ -51,7 +55,7 @@ impl AppWindow {
             }
         };
 
-        match RENDER_ENGINE.diff_and_render_root(self.render_key, (
+        match self.render_engine.diff_and_render_root(self.render_key, (
             self.dimensions.2,
             self.dimensions.3
         ), children) {
@@ -68,6 +72,10 @@ impl AppWindow {
     pub fn set_dimensions(&mut self, x: f64, y: f64, width: f64, height: f64) {
         self.dimensions = (x, y, width, height);
         self.bridge.set_dimensions(x, y, width, height);
+
+        if let Err(e) = self.render_engine.resize_root(self.render_key, (width, height)) {
+            eprintln!("Error resizing window! {}", e);
+        }
     }
 
     /// Renders and calls through to the native platform window show method.
@@ -87,8 +95,15 @@ impl AppWindow {
 pub struct Window(pub(crate) Arc<Mutex<AppWindow>>);
 
 impl Window {
-    /// Creates a new window.
+    /// Creates a new window, with its own `RenderEngine`.
     pub fn new<S: 'static + WindowDelegate>(delegate: S) -> Window {
+        Window::with_render_engine(delegate, RenderEngine::new())
+    }
+
+    /// Creates a new window that renders with `render_engine`. Windows only need to share an
+    /// engine if a `Portal` in one targets another; otherwise, prefer `Window::new()`, so that
+    /// windows can render independently.
+    pub fn with_render_engine<S: 'static + WindowDelegate>(delegate: S, render_engine: Arc<RenderEngine>) -> Window {
         let window_id = SHARED_APP.windows.allocate_new_window_id();
         let view = View::default();
         let shared_app_ptr: *const App = &**SHARED_APP;
@@ -97,7 +112,7 @@ impl Window {
         let backing_node = view.borrow_native_backing_node().unwrap();
         let bridge = PlatformWindowBridge::new(window_id, backing_node, shared_app_ptr);
 
-        let key = match RENDER_ENGINE.register_root_component(view) {
+        let key = match render_engine.register_root_component(view) {
             Ok(key) => key,
             Err(_e) => { panic!("Uhhhh this really messed up"); }
         };
@@ -109,6 +124,7 @@ impl Window {
             dimensions: (0., 0., 0., 0.),
             bridge: bridge,
             delegate: Box::new(delegate),
+            render_engine,
             render_key: key
         })))
     }
@@ -131,6 +147,24 @@ impl Window {
         window.set_dimensions(x, y, width, height);
     }
 
+    /// Returns the `RenderEngine` this window renders with.
+    pub fn render_engine(&self) -> Arc<RenderEngine> {
+        self.0.lock().unwrap().render_engine.clone()
+    }
+
+    /// Returns the `ComponentKey` this window's content view is registered with in the
+    /// `RenderEngine`. This can be used as the target for a `Portal`.
+    pub fn render_key(&self) -> ComponentKey {
+        self.0.lock().unwrap().render_key
+    }
+
+    /// Returns the platform-specific node for this window's content view, which is where the
+    /// rendered tree gets attached. This is mostly useful for inspecting what was rendered.
+    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+        let window = self.0.lock().unwrap();
+        window.bridge.borrow_native_backing_node()
+    }
+
     /// Registers this window with the window manager, renders it, and shows it.
     pub fn show(&self) {
         SHARED_APP.windows.add(self.0.clone());
@@ -0,0 +1,73 @@
+//! Accessibility properties, as forwarded to native nodes and exported as a tree.
+
+#![cfg(feature = "headless")]
+#![recursion_limit = "512"]
+
+mod common;
+
+use alchemy::{AccessibilityRole, rsx, RSX, text, Text, View};
+use serde_json::Value;
+
+fn settings(save_label: &str) -> RSX {
+    rsx! {
+        <View styles=["outer"]>
+            <Text styles=["row"] role="heading">{text!("{}", "Settings")}</Text>
+            <View styles=["field"] role="button" label={save_label.to_string()} hint="Saves your settings" tabIndex={0} />
+            <View styles=["field"] role={AccessibilityRole::Button} />
+            <View styles=["field"] tabIndex={1} />
+            <View styles=["hidden"]><Text>{text!("{}", "secret")}</Text></View>
+        </View>
+    }
+}
+
+#[test]
+fn the_accessibility_tree_exports_as_json() {
+    let (engine, root) = common::engine();
+    engine.diff_and_render_root(root, (600., 400.), settings("Save")).unwrap();
+
+    let tree = engine.accessibility_tree(root).unwrap();
+    let json: Value = serde_json::from_str(&tree.to_json().unwrap()).unwrap();
+    assert_eq!(json["role"], "group");
+    assert_eq!(json["frame"]["width"], 600.);
+
+    // The outer View doesn't describe itself, so its children take its place.
+    let heading = &json["children"][0];
+    assert_eq!(heading["role"], "heading");
+    assert_eq!(heading["label"], "Settings");
+
+    let save = &json["children"][1];
+    assert_eq!(save["role"], "button");
+    assert_eq!(save["label"], "Save");
+    assert_eq!(save["hint"], "Saves your settings");
+    assert_eq!(save["is_focusable"], true);
+    assert_eq!(save["frame"]["y"], 20.);
+
+    let unlabeled = tree.find_all(|node| node.role == AccessibilityRole::Button && node.label.is_none());
+    assert_eq!(unlabeled.len(), 1);
+
+    // Anything that can take focus is exposed, even without a role of its own.
+    let focusable = tree.find_all(|node| node.is_focusable && node.role == AccessibilityRole::Group);
+    assert_eq!(focusable.len(), 1);
+    assert!(tree.find_all(|node| node.label == Some("secret".into())).is_empty());
+}
+
+#[test]
+fn accessibility_properties_reach_the_native_node() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), settings("Save")).unwrap();
+    assert!(root_node.children()[0].accessibility().is_none());
+
+    let save = root_node.children()[0].children()[1].clone();
+    let properties = save.accessibility().unwrap();
+    assert_eq!(properties.role, AccessibilityRole::Button);
+    assert_eq!(properties.label, Some("Save".to_string()));
+
+    // Like anything else native, they're only updated once the render is committed.
+    let patches = engine.diff_root(root, (600., 400.), settings("Save all")).unwrap();
+    assert_eq!(save.accessibility().unwrap().label, Some("Save".to_string()));
+
+    engine.commit(patches).unwrap();
+    assert_eq!(save.accessibility().unwrap().label, Some("Save all".to_string()));
+}
@@ -0,0 +1,85 @@
+//! Setup shared by the integration tests, which render against the headless backend.
+
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use alchemy::{ComponentKey, Error, RenderEngine, RSX, styles, View, Window, WindowDelegate};
+
+static REGISTER_STYLES: Once = Once::new();
+
+/// Registers the stylesheet every test lays out with. Only the first stylesheet registered for a
+/// theme sticks, so tests can't bring their own.
+pub fn register_styles() {
+    REGISTER_STYLES.call_once(|| {
+        alchemy::shared_app().register_styles("default", styles! {
+            box { width: 300; height: 100; background-color: #ff0000; }
+            row { width: 100; height: 20; }
+            outer { width: 600; height: 300; flex-direction: column; }
+            clip { width: 100; height: 50; overflow: hidden; }
+            big { width: 500; height: 100; }
+            hidden { width: 600; height: 50; display: none; }
+            field { width: 10; height: 10; }
+            highlighted { width: 100; height: 20; background-color: #00ff00; }
+            stack { width: 300; height: 100; flex-direction: column; align-items: flex-start; }
+            label { font-size: 20; }
+            heading { width: 100; height: 20; font-size: 20; }
+            caption { width: 200; height: 30; color: #0000ff; opacity: 0.5; font-size: 16; text-align: center; }
+        });
+    });
+}
+
+/// A `WindowDelegate` that renders whatever it was last handed.
+#[derive(Clone)]
+pub struct Scene(Arc<Mutex<Box<Fn() -> RSX + Send>>>);
+
+impl Scene {
+    /// Replaces what gets rendered, from the next render on.
+    pub fn set<F: Fn() -> RSX + Send + 'static>(&self, render: F) {
+        *self.0.lock().unwrap() = Box::new(render);
+    }
+}
+
+impl WindowDelegate for Scene {
+    fn render(&self) -> Result<RSX, Error> {
+        Ok((self.0.lock().unwrap())())
+    }
+}
+
+/// Creates a 600x400 `Window`, along with the `Scene` it renders. Nothing's rendered until it's
+/// shown.
+pub fn window() -> (Window, Scene) {
+    register_styles();
+
+    let scene = Scene(Arc::new(Mutex::new(Box::new(|| RSX::None))));
+    let mut window = Window::new(scene.clone());
+    window.set_dimensions(0., 0., 600., 400.);
+    (window, scene)
+}
+
+/// Creates a fresh `RenderEngine`, with a root registered the way a `Window` registers one.
+pub fn engine() -> (Arc<RenderEngine>, ComponentKey) {
+    register_styles();
+
+    let engine = RenderEngine::new();
+    let root = engine.register_root_component(View::default()).unwrap();
+    (engine, root)
+}
+
+/// Waits (for up to a few seconds) for `condition` to hold, for things that happen on another
+/// thread. Returns whether it did.
+pub fn wait_until<F: Fn() -> bool>(condition: F) -> bool {
+    let started = Instant::now();
+
+    while started.elapsed() < Duration::from_secs(5) {
+        if condition() {
+            return true;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    condition()
+}
@@ -0,0 +1,214 @@
+//! Hit testing, event propagation and keyboard focus, against laid out headless trees.
+
+#![cfg(feature = "headless")]
+#![recursion_limit = "512"]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use alchemy::{Component, ComponentKey, Error, Event, KeyboardEvent, Modifiers, MouseButton, MouseEvent, Props, rsx, RSX, text, Text, View};
+use alchemy_styles::styles::Point;
+
+fn mouse(x: f32, y: f32) -> MouseEvent {
+    MouseEvent::new(Point { x, y }, MouseButton::Left, Modifiers::default())
+}
+
+fn key(key: &str, shift: bool) -> Event {
+    Event::KeyDown(KeyboardEvent::new(key.into(), Modifiers { shift, ..Modifiers::default() }))
+}
+
+#[test]
+fn hit_testing_follows_layout_clipping_and_display() {
+    let (engine, root) = common::engine();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["outer"]>
+            <View styles=["clip"]>
+                <View styles=["big"] />
+            </View>
+            <View styles=["hidden"] />
+            <View styles=["box"]>
+                <Text styles=["row"]>{text!("{}", "hi")}</Text>
+            </View>
+        </View>
+    }).unwrap();
+
+    let tags = |x: f32, y: f32| -> Vec<&'static str> {
+        let path = engine.hit_test(root, Point { x, y }).unwrap();
+        path.iter().map(|key| engine.inspect(*key).unwrap().tag).collect()
+    };
+
+    assert_eq!(tags(5., 5.), vec!["root", "View", "View", "View"]);
+    assert_eq!(tags(150., 20.), vec!["root", "View"]);
+    assert_eq!(tags(5., 60.), vec!["root", "View", "View", "Text"]);
+    assert_eq!(tags(150., 60.), vec!["root", "View", "View"]);
+    assert_eq!(tags(5., 350.), vec!["root"]);
+}
+
+#[test]
+fn clicks_capture_down_and_bubble_up() {
+    let (engine, root) = common::engine();
+    let log = Arc::new(Mutex::new(vec![]));
+    let (l1, l2, l3) = (log.clone(), log.clone(), log.clone());
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["outer"] onClickCapture={move |_| l1.lock().unwrap().push("outer capture")} onClick={move |_| l2.lock().unwrap().push("outer bubble")}>
+            <View styles=["box"] onClick={move |_| l3.lock().unwrap().push("box")} />
+        </View>
+    }).unwrap();
+
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+    let boxed = root_node.children()[0].children()[0].clone();
+    boxed.dispatch_event(Event::MouseDown(mouse(3., 3.))).unwrap();
+    boxed.dispatch_event(Event::MouseUp(mouse(3., 3.))).unwrap();
+
+    assert_eq!(*log.lock().unwrap(), vec!["outer capture", "box", "outer bubble"]);
+}
+
+#[test]
+fn events_bubble_to_handlers_and_clicks_are_synthesized() {
+    let (engine, root) = common::engine();
+    let log = Arc::new(Mutex::new(vec![]));
+    let (l1, l2) = (log.clone(), log.clone());
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["outer"] onClick={move |_| l1.lock().unwrap().push("outer click")}>
+            <View styles=["box"] onMouseDown={move |_| l2.lock().unwrap().push("box down")}>
+                <Text styles=["row"]>{text!("{}", "hi")}</Text>
+            </View>
+            <View styles=["box"] />
+        </View>
+    }).unwrap();
+
+    let outer = engine.native_node_for(root).unwrap().unwrap().children()[0].clone();
+    let label = outer.children()[0].children()[0].clone();
+    label.dispatch_event(Event::MouseDown(mouse(3., 3.))).unwrap();
+    label.dispatch_event(Event::MouseUp(mouse(3., 3.))).unwrap();
+    assert_eq!(*log.lock().unwrap(), vec!["box down", "outer click"]);
+
+    // A mouse up somewhere other than where the mouse went down isn't a click.
+    label.dispatch_event(Event::MouseDown(mouse(3., 3.))).unwrap();
+    outer.children()[1].dispatch_event(Event::MouseUp(mouse(3., 3.))).unwrap();
+    assert_eq!(*log.lock().unwrap(), vec!["box down", "outer click", "box down"]);
+}
+
+fn clickable(log: &Arc<Mutex<Vec<&'static str>>>, label: &'static str, present: bool) -> RSX {
+    let log = log.clone();
+    let boxed = match present {
+        true => rsx! { <View styles=["box"] onClick={move |_| log.lock().unwrap().push(label)} /> },
+        false => RSX::None
+    };
+
+    rsx! { <View styles=["outer"]>{boxed}</View> }
+}
+
+#[test]
+fn events_reach_whichever_engine_mounted_the_node() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let (first, first_root) = common::engine();
+    let (second, second_root) = common::engine();
+
+    first.diff_and_render_root(first_root, (600., 400.), clickable(&log, "first", true)).unwrap();
+    second.diff_and_render_root(second_root, (600., 400.), clickable(&log, "second", true)).unwrap();
+
+    let click = |engine: &Arc<alchemy::RenderEngine>, root: ComponentKey| {
+        let root_node = engine.native_node_for(root).unwrap().unwrap();
+        let boxed = root_node.children()[0].children()[0].clone();
+        boxed.dispatch_event(Event::MouseDown(mouse(3., 3.))).unwrap();
+        boxed.dispatch_event(Event::MouseUp(mouse(3., 3.))).unwrap();
+        boxed
+    };
+
+    click(&second, second_root);
+    let unmounted = click(&first, first_root);
+    assert_eq!(*log.lock().unwrap(), vec!["second", "first"]);
+
+    // Once the node is unmounted, there's nothing for its events to reach.
+    first.diff_and_render_root(first_root, (600., 400.), clickable(&log, "first", false)).unwrap();
+    unmounted.dispatch_event(Event::MouseDown(mouse(3., 3.))).unwrap();
+    unmounted.dispatch_event(Event::MouseUp(mouse(3., 3.))).unwrap();
+    assert_eq!(log.lock().unwrap().len(), 2);
+
+    first.diff_and_render_root(first_root, (600., 400.), clickable(&log, "remounted", true)).unwrap();
+    click(&first, first_root);
+    assert_eq!(log.lock().unwrap().last(), Some(&"remounted"));
+}
+
+#[derive(Clone, Default)]
+struct FieldProps {
+    name: String,
+    log: Option<Arc<Mutex<Vec<String>>>>
+}
+
+#[derive(Props)]
+struct Field {
+    props: FieldProps
+}
+
+impl Field {
+    fn log(&self, message: &str) {
+        if let Some(log) = &self.props.log {
+            log.lock().unwrap().push(format!("{} {}", message, self.props.name));
+        }
+    }
+}
+
+impl Component for Field {
+    fn new(_: ComponentKey) -> Field {
+        Field { props: FieldProps::default() }
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn component_did_focus(&mut self) {
+        self.log("focus");
+    }
+
+    fn component_did_blur(&mut self) {
+        self.log("blur");
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <View styles=["field"] /> })
+    }
+}
+
+#[test]
+fn tab_moves_focus_in_tab_order() {
+    let (engine, root) = common::engine();
+    let log = Arc::new(Mutex::new(vec![]));
+    let field = |name: &str| (name.to_string(), Some(log.clone()));
+    let ((a, a_log), (b, b_log), (c, c_log), (d, d_log), (hidden, hidden_log)) =
+        (field("a"), field("b"), field("c"), field("d"), field("hidden"));
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["outer"]>
+            <Field name={a} log={a_log} />
+            <Field name={b} log={b_log} />
+            <View styles=["hidden"]><Field name={hidden} log={hidden_log} /></View>
+            <Field name={c} log={c_log} tabIndex={1} />
+            <Field name={d} log={d_log} tabIndex={-1} />
+        </View>
+    }).unwrap();
+
+    let outer = engine.native_node_for(root).unwrap().unwrap().children()[0].clone();
+    for _ in 0..4 {
+        outer.dispatch_event(key("\t", false)).unwrap();
+    }
+
+    outer.dispatch_event(key("\t", true)).unwrap();
+
+    let logged: Vec<String> = log.lock().unwrap().drain(..).collect();
+    assert_eq!(logged, vec![
+        "focus c", "blur c", "focus a", "blur a", "focus b", "blur b", "focus c",
+        "blur c", "focus b"
+    ]);
+
+    assert!(engine.focused_component(root).unwrap().is_some());
+    engine.clear_focus(root).unwrap();
+    assert!(engine.focused_component(root).unwrap().is_none());
+    assert_eq!(*log.lock().unwrap(), vec!["blur b"]);
+}
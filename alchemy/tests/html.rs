@@ -0,0 +1,31 @@
+//! Renders trees to static HTML, and checks the markup that comes out.
+
+#![cfg(feature = "headless")]
+
+mod common;
+
+use alchemy::{render_to_html, rsx, text, Text, View};
+
+#[test]
+fn trees_render_to_absolutely_positioned_divs() {
+    common::register_styles();
+
+    let html = render_to_html(rsx! {
+        <View styles=["box"]>
+            <View styles=["row"] />
+            <Text styles=["caption", "fine-print"]>{text!("{}", "1 < 2 & \"3\" > 'x'")}</Text>
+        </View>
+    }, 600., 400.).unwrap();
+
+    assert_eq!(html, concat!(
+        "<div data-tag=\"root\" class=\"root\" style=\"position: relative; left: 0px; top: 0px; width: 600px; height: 400px;\">\n",
+        "  <div data-tag=\"View\" class=\"box\" style=\"position: absolute; left: 0px; top: 0px; width: 300px; height: 100px; ",
+            "background-color: rgba(255, 0, 0, 1);\">\n",
+        "    <div data-tag=\"View\" class=\"row\" style=\"position: absolute; left: 0px; top: 0px; width: 100px; height: 20px;\"></div>\n",
+        "    <div data-tag=\"Text\" class=\"caption fine-print\" style=\"position: absolute; left: 100px; top: 0px; width: 200px; height: 30px; ",
+            "opacity: 0.5; color: rgba(0, 0, 255, 1); font-size: 16px; text-align: center;\">",
+            "1 &lt; 2 &amp; &quot;3&quot; &gt; &#x27;x&#x27;</div>\n",
+        "  </div>\n",
+        "</div>\n"
+    ));
+}
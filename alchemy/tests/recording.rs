@@ -0,0 +1,185 @@
+//! Records render sessions, and replays them into fresh engines.
+
+#![cfg(feature = "headless")]
+#![recursion_limit = "512"]
+
+mod common;
+
+use std::sync::Arc;
+
+use alchemy::{Component, ComponentKey, Error, Event, Modifiers, MouseButton, MouseEvent, Props, Recording, Ref, RenderEngine, rsx, RSX, RENDER_ENGINE, text, Text, View};
+use alchemy_lifecycle::recording::Action;
+use alchemy_styles::styles::Point;
+use matches::matches;
+
+#[derive(Clone, Default)]
+struct CounterProps {}
+
+#[derive(Props)]
+struct Counter {
+    key: ComponentKey,
+    count: usize
+}
+
+impl Component for Counter {
+    fn new(key: ComponentKey) -> Counter {
+        Counter { key, count: 0 }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        let key = self.key;
+        let extra = match self.count % 2 {
+            1 => rsx! { <View styles=["field"] /> },
+            _ => RSX::None
+        };
+
+        Ok(rsx! {
+            <View styles=["box"] onClick={move |_: &MouseEvent| {
+                RENDER_ENGINE.queue_update_for(key, |counter: &mut Counter| counter.count += 1);
+            }}>
+                <Text styles=["row"]>{text!("count {}", self.count)}</Text>
+                {extra}
+            </View>
+        })
+    }
+}
+
+fn click(engine: &Arc<RenderEngine>, root: ComponentKey) {
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+    let boxed = root_node.find(|node| node.kind() == "Text").unwrap().parent().unwrap();
+    let mouse = MouseEvent::new(Point { x: 1., y: 1. }, MouseButton::Left, Modifiers::default());
+    boxed.dispatch_event(Event::MouseDown(mouse.clone())).unwrap();
+    boxed.dispatch_event(Event::MouseUp(mouse)).unwrap();
+}
+
+fn record_session() -> Recording {
+    let (engine, root) = common::engine();
+    engine.start_recording();
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter /> }).unwrap();
+    click(&engine, root);
+    engine.resize_root(root, (500., 200.)).unwrap();
+
+    let recording = engine.stop_recording().unwrap();
+    assert!(engine.stop_recording().is_none());
+    recording
+}
+
+#[test]
+fn recordings_round_trip_through_json() {
+    let recording = record_session();
+    assert_eq!(Recording::from_json(&recording.to_json().unwrap()).unwrap(), recording);
+
+    let mut future = recording.clone();
+    future.version += 1;
+    assert!(Recording::from_json(&future.to_json().unwrap()).is_err());
+}
+
+#[test]
+fn replaying_a_recording_reproduces_it() {
+    let recording = record_session();
+    let (engine, root) = common::engine();
+
+    assert_eq!(recording.replay(&engine, root, || rsx! { <Counter /> }).unwrap(), None);
+
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+    let label = root_node.find(|node| node.kind() == "Text").unwrap();
+    assert_eq!(label.text(), Some("count 1".to_string()));
+}
+
+#[test]
+fn replaying_reports_where_a_session_diverged() {
+    let recording = record_session();
+
+    // Without the click (and the update it queued), the commit it caused never happens.
+    let mut without_input = recording.clone();
+    without_input.entries.retain(|entry| !matches!(entry.action, Action::Input { .. } | Action::StateUpdate { .. }));
+
+    let (engine, root) = common::engine();
+    let divergence = without_input.replay(&engine, root, || rsx! { <Counter /> }).unwrap().unwrap();
+    assert!(divergence.expected.is_some());
+
+    // Rendering something else differs from the very first commit.
+    let (engine, root) = common::engine();
+    let divergence = recording.replay(&engine, root, || rsx! { <View styles=["box"] /> }).unwrap().unwrap();
+    let first_commit = recording.entries.iter().position(|entry| matches!(entry.action, Action::Commit { .. })).unwrap();
+    assert_eq!(divergence.entry, first_commit);
+    assert!(divergence.expected.is_some() && divergence.actual.is_some());
+}
+
+#[test]
+fn replaying_skips_commits_from_updates_it_cant_reproduce() {
+    let (engine, root) = common::engine();
+    let counter = Ref::new();
+
+    // As though a network response had come in after rendering...
+    engine.start_recording();
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter ref={counter.clone()} /> }).unwrap();
+    engine.queue_update_for(counter.key().unwrap(), |counter: &mut Counter| counter.count += 2);
+    engine.flush_queued_updates().unwrap();
+    click(&engine, root);
+    let recording = engine.stop_recording().unwrap();
+
+    let mut without_input = recording.clone();
+    let input = without_input.entries.iter().position(|entry| matches!(entry.action, Action::Input { .. })).unwrap();
+    without_input.entries.truncate(input);
+
+    let (engine, root) = common::engine();
+    assert_eq!(without_input.replay(&engine, root, || rsx! { <Counter /> }).unwrap(), None);
+
+    // ...its effects still show up once something else happens.
+    let (engine, root) = common::engine();
+    let divergence = recording.replay(&engine, root, || rsx! { <Counter /> }).unwrap().unwrap();
+    assert!(divergence.entry > input);
+}
+
+#[test]
+fn replaying_forces_updates_that_came_from_elsewhere() {
+    let (engine, root) = common::engine();
+    let counter = Ref::new();
+
+    engine.start_recording();
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter ref={counter.clone()} /> }).unwrap();
+    engine.force_update(counter.key().unwrap()).unwrap();
+    let recording = engine.stop_recording().unwrap();
+
+    let commits = recording.entries.iter().filter(|entry| matches!(entry.action, Action::Commit { .. })).count();
+    assert_eq!(commits, 2);
+
+    let (engine, root) = common::engine();
+    assert_eq!(recording.replay(&engine, root, || rsx! { <Counter /> }).unwrap(), None);
+}
+
+#[test]
+fn replaying_puts_back_whatever_was_listening_and_recording() {
+    let recording = record_session();
+    let (engine, root) = common::engine();
+
+    let commits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let listener_commits = commits.clone();
+    engine.on_commit(move |_| { listener_commits.fetch_add(1, std::sync::atomic::Ordering::SeqCst); });
+    engine.start_recording();
+
+    assert_eq!(recording.replay(&engine, root, || rsx! { <Counter /> }).unwrap(), None);
+    assert_eq!(commits.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <View styles=["box"] /> }).unwrap();
+    assert_eq!(commits.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    let kept = engine.stop_recording().unwrap();
+    let kept_commits = kept.entries.iter().filter(|entry| matches!(entry.action, Action::Commit { .. })).count();
+    assert_eq!(kept_commits, 1);
+}
+
+#[test]
+fn updates_forced_on_other_engines_arent_recorded() {
+    let (engine, root) = common::engine();
+    let (other, other_root) = common::engine();
+    drop(other);
+
+    engine.start_recording();
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter /> }).unwrap();
+    let _ = engine.force_update(other_root);
+    let recording = engine.stop_recording().unwrap();
+
+    assert!(!recording.entries.iter().any(|entry| matches!(entry.action, Action::StateUpdate { .. })));
+}
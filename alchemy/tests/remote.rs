@@ -0,0 +1,210 @@
+//! Mirrors a tree rendered by one `RenderEngine` under another's root, over a connected pair of
+//! sockets, with events flowing back the other way.
+
+#![cfg(all(feature = "headless", unix))]
+#![recursion_limit = "512"]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use alchemy::{Component, ComponentKey, Error, Event, Fragment, Modifiers, MouseButton, MouseEvent, Props, rsx, RSX, RENDER_ENGINE, text, Text, View};
+use alchemy::remote::{self, Connection, Message, NodeId, Operation};
+use alchemy_styles::styles::Point;
+
+#[derive(Clone, Default)]
+struct CounterProps {}
+
+#[derive(Props)]
+struct Counter {
+    key: ComponentKey,
+    count: usize
+}
+
+impl Component for Counter {
+    fn new(key: ComponentKey) -> Counter {
+        Counter { key, count: 0 }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        let key = self.key;
+        let extra = match self.count % 2 {
+            1 => rsx! { <View styles=["field"] /> },
+            _ => RSX::None
+        };
+
+        Ok(rsx! {
+            <View styles=["box"] onClick={move |_: &MouseEvent| {
+                RENDER_ENGINE.queue_update_for(key, |counter: &mut Counter| counter.count += 1);
+            }}>
+                <Text styles=["row"]>{text!("count {}", self.count)}</Text>
+                {extra}
+            </View>
+        })
+    }
+}
+
+fn click() -> Vec<Event> {
+    let mouse = MouseEvent::new(Point { x: 1., y: 1. }, MouseButton::Left, Modifiers::default());
+    vec![Event::MouseDown(mouse.clone()), Event::MouseUp(mouse)]
+}
+
+#[test]
+fn mirrors_commits_and_sends_events_back() {
+    let (back_connection, front_connection) = Connection::pair().unwrap();
+    let back_connection = Arc::new(back_connection);
+
+    let (front, front_root) = common::engine();
+    let mirror = remote::mirror(front_root, Arc::new(front_connection));
+    let running_mirror = mirror.clone();
+    thread::spawn(move || running_mirror.run().unwrap());
+
+    let (back, back_root) = common::engine();
+    remote::stream_commits(&back, back_connection.clone());
+    let dispatching_engine = back.clone();
+    let dispatching_connection = back_connection.clone();
+    thread::spawn(move || remote::dispatch_events(&dispatching_engine, &dispatching_connection).unwrap());
+
+    back.diff_and_render_root(back_root, (600., 400.), rsx! { <Counter /> }).unwrap();
+
+    let front_node = front.native_node_for(front_root).unwrap().unwrap();
+    let label = || front_node.find(|node| node.kind() == "Text");
+    assert!(common::wait_until(|| label().is_some()));
+    assert_eq!(label().unwrap().text(), Some("count 0".to_string()));
+    assert_eq!(label().unwrap().size().width, 100.);
+
+    let boxed = label().unwrap().parent().unwrap();
+    for event in click() {
+        boxed.dispatch_event(event).unwrap();
+    }
+
+    assert!(common::wait_until(|| label().unwrap().text() == Some("count 1".to_string())));
+    assert!(common::wait_until(|| boxed.children().len() == 2));
+
+    let back_node = back.native_node_for(back_root).unwrap().unwrap();
+    let back_label = back_node.find(|node| node.kind() == "Text").unwrap();
+    assert_eq!(back_label.text(), Some("count 1".to_string()));
+    assert_eq!(back_label.parent().unwrap().children().len(), 2);
+}
+
+#[test]
+fn each_root_has_its_own_node_id_and_mirrors_stick_to_one() {
+    let (back, first_root) = common::engine();
+    let second_root = back.register_root_component(View::default()).unwrap();
+
+    let commits = Arc::new(Mutex::new(vec![]));
+    let listener_commits = commits.clone();
+    back.on_commit(move |operations| listener_commits.lock().unwrap().push(operations.to_vec()));
+
+    back.diff_and_render_root(first_root, (600., 400.), rsx! { <Counter /> }).unwrap();
+    back.diff_and_render_root(second_root, (600., 400.), rsx! { <View styles=["field"] /> }).unwrap();
+
+    let commits = commits.lock().unwrap().clone();
+    let roots: Vec<NodeId> = commits.iter().flatten().filter_map(|operation| match operation {
+        Operation::Root { id } => Some(*id),
+        _ => None
+    }).collect();
+
+    assert_eq!(roots.len(), 2);
+    assert!(roots[0] != roots[1]);
+
+    // Only the first root's tree makes it under the mirror's.
+    let (front, front_root) = common::engine();
+    let (connection, _) = Connection::pair().unwrap();
+    let mirror = remote::mirror(front_root, Arc::new(connection));
+    for operations in &commits {
+        mirror.apply(operations).unwrap();
+    }
+
+    let front_node = front.native_node_for(front_root).unwrap().unwrap();
+    assert_eq!(front_node.children().len(), 1);
+    assert!(front_node.find(|node| node.kind() == "Text").is_some());
+}
+
+#[test]
+fn mirrored_nodes_go_after_the_roots_own() {
+    let (back, back_root) = common::engine();
+    let commits = Arc::new(Mutex::new(vec![]));
+    let listener_commits = commits.clone();
+    back.on_commit(move |operations| listener_commits.lock().unwrap().push(operations.to_vec()));
+    back.diff_and_render_root(back_root, (600., 400.), rsx! { <Counter /> }).unwrap();
+
+    let (front, front_root) = common::engine();
+    front.diff_and_render_root(front_root, (600., 400.), rsx! { <View styles=["row"] /> }).unwrap();
+
+    let (connection, _) = Connection::pair().unwrap();
+    let mirror = remote::mirror(front_root, Arc::new(connection));
+    for operations in commits.lock().unwrap().iter() {
+        mirror.apply(operations).unwrap();
+    }
+
+    let front_node = front.native_node_for(front_root).unwrap().unwrap();
+    let widths = || front_node.children().iter().map(|child| child.size().width).collect::<Vec<f32>>();
+    assert_eq!(widths(), vec![100., 300.]);
+
+    // The root's own children are still placed where its engine expects them.
+    front.diff_and_render_root(front_root, (600., 400.), rsx! {
+        <Fragment>
+            <View styles=["field"] />
+            <View styles=["row"] />
+        </Fragment>
+    }).unwrap();
+
+    assert_eq!(widths(), vec![10., 100., 300.]);
+}
+
+#[test]
+fn events_for_nodes_in_other_engines_are_rejected() {
+    let (back, back_root) = common::engine();
+    let (other, other_root) = common::engine();
+
+    let targets = Arc::new(Mutex::new(vec![]));
+    for (engine, root) in &[(&back, back_root), (&other, other_root)] {
+        let engine_targets = targets.clone();
+        engine.on_commit(move |operations| {
+            engine_targets.lock().unwrap().extend(operations.iter().filter_map(|operation| match operation {
+                Operation::Create { id, kind } if kind == "View" => Some(*id),
+                _ => None
+            }));
+        });
+
+        engine.diff_and_render_root(*root, (600., 400.), rsx! { <Counter /> }).unwrap();
+    }
+
+    // The same tree in two engines is keyed the same way within each, but not on the wire.
+    let targets = targets.lock().unwrap().clone();
+    assert_eq!(targets.len(), 2);
+    assert!(targets[0] != targets[1]);
+    assert_eq!(targets[0] as u32, targets[1] as u32);
+
+    let (sender, receiver) = Connection::pair().unwrap();
+    let sending = thread::spawn(move || {
+        for target in &[targets[1], targets[1] as u32 as NodeId, !0] {
+            for event in click() {
+                sender.send(&Message::Event { target: *target, event }).unwrap();
+            }
+        }
+    });
+
+    // Hanging up once everything's sent lets `dispatch_events()` return.
+    remote::dispatch_events(&back, &receiver).unwrap();
+    sending.join().unwrap();
+
+    let back_node = back.native_node_for(back_root).unwrap().unwrap();
+    let label = back_node.find(|node| node.kind() == "Text").unwrap();
+    assert_eq!(label.text(), Some("count 0".to_string()));
+}
+
+#[test]
+fn messages_meant_for_the_other_end_are_errors() {
+    let (back, _) = common::engine();
+    let (sender, receiver) = Connection::pair().unwrap();
+    sender.send(&Message::Commit(vec![])).unwrap();
+    assert!(remote::dispatch_events(&back, &receiver).is_err());
+
+    let (_, front_root) = common::engine();
+    let (sender, receiver) = Connection::pair().unwrap();
+    sender.send(&Message::Event { target: 1, event: click().remove(0) }).unwrap();
+    assert!(remote::mirror(front_root, Arc::new(receiver)).run().is_err());
+}
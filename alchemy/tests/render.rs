@@ -0,0 +1,857 @@
+//! Renders through the whole pipeline - diffing, layout, and committing to native nodes - and
+//! checks what lands in the headless tree.
+
+#![cfg(feature = "headless")]
+#![recursion_limit = "512"]
+
+mod common;
+
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alchemy::{Color, Component, ComponentKey, Context, Error, ErrorInfo, Portal, Props, Ref, rsx, RSX, RENDER_ENGINE, text, Text, View, Window, WindowDelegate};
+use alchemy_lifecycle::headless::NativeNode;
+use alchemy_styles::Appearance;
+use alchemy_styles::styles::{MeasureFunc, Size};
+
+struct Greeting;
+
+impl WindowDelegate for Greeting {
+    fn render(&self) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <View styles=["box"]>
+                <Text styles=["row"]>{text!("{}", "hello")}</Text>
+            </View>
+        })
+    }
+}
+
+#[test]
+fn showing_a_window_renders_lays_out_and_styles_it() {
+    common::register_styles();
+
+    let mut window = Window::new(Greeting);
+    window.set_dimensions(0., 0., 600., 400.);
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    assert_eq!(content.size().width, 600.);
+    assert_eq!(content.size().height, 400.);
+
+    let boxed = content.children()[0].clone();
+    assert_eq!(boxed.kind(), "View");
+    assert_eq!((boxed.size().width, boxed.size().height), (300., 100.));
+    assert_eq!(boxed.appearance().background_color, Color::new(255, 0, 0, 255));
+
+    let label = boxed.children()[0].clone();
+    assert_eq!(label.kind(), "Text");
+    assert_eq!(label.text(), Some("hello".to_string()));
+    assert_eq!((label.size().width, label.size().height), (100., 20.));
+}
+
+fn rows(labels: &[&str]) -> RSX {
+    rsx! {
+        <View styles=["box"]>
+            {labels.iter().map(|label| rsx! {
+                <Text key={label.to_string()} styles=["row"]>{text!("{}", label)}</Text>
+            })}
+        </View>
+    }
+}
+
+#[test]
+fn keyed_children_keep_their_native_nodes_when_reordered() {
+    let (window, scene) = common::window();
+    scene.set(|| rows(&["a", "b", "c"]));
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    let before = content.children()[0].children();
+
+    scene.set(|| rows(&["c", "a", "b"]));
+    window.render();
+    let after = content.children()[0].children();
+
+    let texts: Vec<Option<String>> = after.iter().map(|node| node.text()).collect();
+    assert_eq!(texts, vec![Some("c".into()), Some("a".into()), Some("b".into())]);
+
+    let ids: Vec<usize> = after.iter().map(|node| node.id()).collect();
+    assert_eq!(ids, vec![before[2].id(), before[0].id(), before[1].id()]);
+}
+
+fn view_or_text(text: bool) -> RSX {
+    let child = match text {
+        true => rsx! { <Text styles=["row"]>{text!("{}", "swapped")}</Text> },
+        false => rsx! { <View styles=["row"] /> }
+    };
+
+    rsx! {
+        <View styles=["box"]>
+            <View styles=["row"] />
+            {child}
+            <View styles=["row"] />
+        </View>
+    }
+}
+
+#[test]
+fn changing_a_tag_replaces_the_node_in_place() {
+    let (window, scene) = common::window();
+    scene.set(|| view_or_text(false));
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    let old = content.children()[0].children()[1].clone();
+
+    scene.set(|| view_or_text(true));
+    window.render();
+    let children = content.children()[0].children();
+    let kinds: Vec<&str> = children.iter().map(|node| node.kind()).collect();
+    assert_eq!(kinds, vec!["View", "Text", "View"]);
+    assert_eq!(children[1].text(), Some("swapped".to_string()));
+    assert!(old.parent().is_none());
+}
+
+static UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct LabelProps {
+    label: String
+}
+
+#[derive(Props)]
+struct Label {
+    props: LabelProps
+}
+
+impl Component for Label {
+    fn new(_: ComponentKey) -> Label {
+        Label { props: LabelProps::default() }
+    }
+
+    fn component_did_update(&mut self, _: &Any, _: Option<Box<Any + Send>>) {
+        UPDATES.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <Text styles=["row"]>{text!("{}", self.props.label)}</Text> })
+    }
+}
+
+#[test]
+fn matched_components_get_new_props_and_re_render() {
+    let (window, scene) = common::window();
+    scene.set(|| rsx! { <Label label={"before".to_string()} /> });
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    let label = content.children()[0].clone();
+    assert_eq!(label.text(), Some("before".to_string()));
+    assert_eq!(UPDATES.load(Ordering::SeqCst), 0);
+
+    scene.set(|| rsx! { <Label label={"after".to_string()} /> });
+    window.render();
+    assert_eq!(content.children()[0], label);
+    assert_eq!(label.text(), Some("after".to_string()));
+    assert_eq!(UPDATES.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Clone, Default)]
+struct BombProps {
+    panics: bool
+}
+
+#[derive(Props)]
+struct Bomb {
+    props: BombProps
+}
+
+impl Component for Bomb {
+    fn new(_: ComponentKey) -> Bomb {
+        Bomb { props: BombProps::default() }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        match self.props.panics {
+            true => panic!("kaboom"),
+            false => Err("kaboom".into())
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct BoundaryProps {}
+
+static CAUGHT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Props)]
+struct Boundary {
+    error: Option<String>
+}
+
+impl Component for Boundary {
+    fn new(_: ComponentKey) -> Boundary {
+        Boundary { error: None }
+    }
+
+    fn get_derived_state_from_error(&mut self, error: &Error) -> bool {
+        self.error = Some(error.to_string());
+        true
+    }
+
+    fn component_did_catch(&mut self, _: &Error, _: &ErrorInfo) {
+        CAUGHT.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(match &self.error {
+            Some(error) => rsx! { <Text styles=["row"]>{text!("fallback: {}", error)}</Text> },
+            None => rsx! { <View styles=["row"]>{children}</View> }
+        })
+    }
+}
+
+#[test]
+fn error_boundaries_render_a_fallback_for_errors_and_panics() {
+    for panics in &[false, true] {
+        let panics = *panics;
+        let (window, scene) = common::window();
+        scene.set(move || rsx! {
+            <View styles=["box"]>
+                <Boundary><Bomb panics={panics} /></Boundary>
+            </View>
+        });
+        window.show();
+
+        let content = window.borrow_native_backing_node();
+        let fallback = content.children()[0].children()[0].clone();
+        assert_eq!(fallback.kind(), "Text");
+        assert!(fallback.text().unwrap().starts_with("fallback: "));
+        assert!(fallback.text().unwrap().contains("kaboom"));
+
+        assert_eq!(CAUGHT.swap(0, Ordering::SeqCst), 1);
+    }
+}
+
+#[derive(Clone, Default)]
+struct SnapshottingProps {
+    label: String,
+    log: Option<Arc<Mutex<Vec<String>>>>
+}
+
+/// Logs what it's handed before and after each update.
+#[derive(Props)]
+struct Snapshotting {
+    props: SnapshottingProps
+}
+
+impl Snapshotting {
+    fn log(&self, message: String) {
+        if let Some(log) = &self.props.log {
+            log.lock().unwrap().push(message);
+        }
+    }
+}
+
+impl Component for Snapshotting {
+    fn new(_: ComponentKey) -> Snapshotting {
+        Snapshotting { props: SnapshottingProps::default() }
+    }
+
+    fn get_snapshot_before_update(&self, prev_props: &Any) -> Option<Box<Any + Send>> {
+        let prev_props = prev_props.downcast_ref::<SnapshottingProps>().unwrap();
+        self.log(format!("snapshot {} -> {}", prev_props.label, self.props.label));
+        Some(Box::new(format!("taken at {}", prev_props.label)))
+    }
+
+    fn component_did_update(&mut self, prev_props: &Any, snapshot: Option<Box<Any + Send>>) {
+        let prev_props = prev_props.downcast_ref::<SnapshottingProps>().unwrap();
+        let snapshot = snapshot.unwrap().downcast::<String>().unwrap();
+        self.log(format!("updated {} -> {}, {}", prev_props.label, self.props.label, snapshot));
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <Text styles=["row"]>{text!("{}", self.props.label)}</Text> })
+    }
+}
+
+#[test]
+fn snapshots_are_taken_before_updating_and_handed_to_component_did_update() {
+    let (window, scene) = common::window();
+    let log = Arc::new(Mutex::new(vec![]));
+    let snapshotting = |label: &'static str, log: &Arc<Mutex<Vec<String>>>| {
+        let log = log.clone();
+        move || rsx! { <Snapshotting label={label.to_string()} log={Some(log.clone())} /> }
+    };
+
+    scene.set(snapshotting("first", &log));
+    window.show();
+    assert!(log.lock().unwrap().is_empty());
+
+    scene.set(snapshotting("second", &log));
+    window.render();
+    assert_eq!(*log.lock().unwrap(), vec![
+        "snapshot first -> second",
+        "updated first -> second, taken at first"
+    ]);
+}
+
+static MEMO_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default, PartialEq)]
+struct MemoRowProps {
+    label: String
+}
+
+#[derive(Props)]
+#[props(memo)]
+struct MemoRow {
+    props: MemoRowProps
+}
+
+impl Component for MemoRow {
+    fn new(_: ComponentKey) -> MemoRow {
+        MemoRow { props: MemoRowProps::default() }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        MEMO_RENDERS.fetch_add(1, Ordering::SeqCst);
+        Ok(rsx! { <Text styles=["row"]>{text!("{}", self.props.label)}</Text> })
+    }
+}
+
+fn memo_rows(labels: &[String]) -> RSX {
+    rsx! {
+        <View styles=["box"]>
+            {labels.iter().map(|label| rsx! { <MemoRow label={label.clone()} /> })}
+        </View>
+    }
+}
+
+#[test]
+fn memoized_components_skip_rendering_when_props_are_equal() {
+    let (window, scene) = common::window();
+    let labels: Vec<String> = (0..10).map(|i| format!("row {}", i)).collect();
+    let rendered = labels.clone();
+    scene.set(move || memo_rows(&rendered));
+    window.show();
+    assert_eq!(MEMO_RENDERS.swap(0, Ordering::SeqCst), 10);
+
+    let mut labels = labels;
+    labels[5] = "changed".into();
+    scene.set(move || memo_rows(&labels));
+    window.render();
+    assert_eq!(MEMO_RENDERS.swap(0, Ordering::SeqCst), 1);
+
+    let content = window.borrow_native_backing_node();
+    assert_eq!(content.children()[0].children()[5].text(), Some("changed".to_string()));
+}
+
+static LABEL_RENDERS: AtomicUsize = AtomicUsize::new(0);
+static BOX_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default, PartialEq)]
+struct MemoLabelProps {}
+
+/// A memoized `Component` that's handed its text as children.
+#[derive(Props)]
+#[props(memo)]
+struct MemoLabel;
+
+impl Component for MemoLabel {
+    fn new(_: ComponentKey) -> MemoLabel {
+        MemoLabel
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        LABEL_RENDERS.fetch_add(1, Ordering::SeqCst);
+        Ok(rsx! { <Text styles=["row"]>{children}</Text> })
+    }
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct MemoBoxProps {}
+
+/// A memoized `Component` that wraps whatever children it's handed.
+#[derive(Props)]
+#[props(memo)]
+struct MemoBox;
+
+impl Component for MemoBox {
+    fn new(_: ComponentKey) -> MemoBox {
+        MemoBox
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        BOX_RENDERS.fetch_add(1, Ordering::SeqCst);
+        Ok(rsx! { <View styles=["box"]>{children}</View> })
+    }
+}
+
+#[derive(Clone, Default)]
+struct PassthroughProps {
+    handle: Option<Arc<Mutex<Option<ComponentKey>>>>
+}
+
+/// Hands its children down to a `MemoBox` as-is, and its key to `handle` once it's mounted.
+#[derive(Props)]
+struct Passthrough {
+    props: PassthroughProps,
+    key: ComponentKey
+}
+
+impl Component for Passthrough {
+    fn new(key: ComponentKey) -> Passthrough {
+        Passthrough { props: PassthroughProps::default(), key }
+    }
+
+    fn component_did_mount(&mut self) {
+        if let Some(handle) = &self.props.handle {
+            *handle.lock().unwrap() = Some(self.key);
+        }
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <MemoBox>{children}</MemoBox> })
+    }
+}
+
+#[test]
+fn memoized_components_only_skip_rendering_for_text_or_passed_down_children() {
+    let (window, scene) = common::window();
+    let labeled = |label: &'static str| move || rsx! {
+        <View styles=["box"]><MemoLabel>{text!("{}", label)}</MemoLabel></View>
+    };
+
+    // Text children compare by their text.
+    scene.set(labeled("a"));
+    window.show();
+    window.render();
+    assert_eq!(LABEL_RENDERS.swap(0, Ordering::SeqCst), 1);
+
+    scene.set(labeled("b"));
+    window.render();
+    assert_eq!(LABEL_RENDERS.swap(0, Ordering::SeqCst), 1);
+
+    // Anything made anew by `rsx! {}` counts as changed, even if it's the same.
+    scene.set(|| rsx! { <MemoBox><View styles=["row"] /></MemoBox> });
+    window.render();
+    window.render();
+    assert_eq!(BOX_RENDERS.swap(0, Ordering::SeqCst), 2);
+
+    // ...but the same children, passed down again by a parent that re-renders, don't.
+    let handle = Arc::new(Mutex::new(None));
+    let passthrough = handle.clone();
+    scene.set(move || rsx! {
+        <Passthrough handle={Some(passthrough.clone())}><View styles=["row"] /></Passthrough>
+    });
+    window.render();
+    assert_eq!(BOX_RENDERS.swap(0, Ordering::SeqCst), 1);
+
+    let key = handle.lock().unwrap().unwrap();
+    RENDER_ENGINE.force_update(key).unwrap();
+    assert_eq!(BOX_RENDERS.swap(0, Ordering::SeqCst), 0);
+}
+
+/// What `ThemeProvider` hands down through `Context`.
+struct Theme(&'static str);
+
+#[derive(Clone, Default)]
+struct ThemeProviderProps {
+    theme: Option<Arc<Theme>>
+}
+
+#[derive(Props)]
+struct ThemeProvider {
+    props: ThemeProviderProps
+}
+
+impl Component for ThemeProvider {
+    fn new(_: ComponentKey) -> ThemeProvider {
+        ThemeProvider { props: ThemeProviderProps::default() }
+    }
+
+    fn provide_context(&self) -> Option<Arc<Any + Send + Sync>> {
+        self.props.theme.clone().map(|theme| theme as Arc<Any + Send + Sync>)
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <View styles=["box"]>{children}</View> })
+    }
+}
+
+static THEMED_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct ThemedProps {}
+
+/// Shows the name of whatever `Theme` it receives.
+#[derive(Props)]
+struct Themed {
+    theme: Option<Arc<Theme>>
+}
+
+impl Component for Themed {
+    fn new(_: ComponentKey) -> Themed {
+        Themed { theme: None }
+    }
+
+    fn receive_context(&mut self, context: &Context) {
+        self.theme = context.get::<Theme>();
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        THEMED_RENDERS.fetch_add(1, Ordering::SeqCst);
+        let name = self.theme.as_ref().map(|theme| theme.0).unwrap_or("none");
+        Ok(rsx! { <Text styles=["row"]>{text!("{}", name)}</Text> })
+    }
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct MemoFrameProps {}
+
+/// A memoized `Component` with nothing to compare, so it's never re-rendered by its parent.
+#[derive(Props)]
+#[props(memo)]
+struct MemoFrame;
+
+impl Component for MemoFrame {
+    fn new(_: ComponentKey) -> MemoFrame {
+        MemoFrame
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <Themed /> })
+    }
+}
+
+#[derive(Clone, Default)]
+struct FrozenFrameProps {}
+
+/// Never agrees to update.
+#[derive(Props)]
+struct FrozenFrame;
+
+impl Component for FrozenFrame {
+    fn new(_: ComponentKey) -> FrozenFrame {
+        FrozenFrame
+    }
+
+    fn should_component_update(&self) -> bool {
+        false
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <Themed /> })
+    }
+}
+
+#[test]
+fn context_consumers_re_render_when_the_provided_value_changes() {
+    let (window, scene) = common::window();
+    let themed = |theme: &Arc<Theme>| {
+        let theme = theme.clone();
+        move || rsx! {
+            <ThemeProvider theme={Some(theme.clone())}>
+                <MemoFrame />
+                <FrozenFrame />
+            </ThemeProvider>
+        }
+    };
+
+    let names = || -> Vec<Option<String>> {
+        let content = window.borrow_native_backing_node();
+        content.children()[0].children().iter().map(|node| node.text()).collect()
+    };
+
+    let light = Arc::new(Theme("light"));
+    scene.set(themed(&light));
+    window.show();
+    assert_eq!(names(), vec![Some("light".to_string()), Some("light".to_string())]);
+    assert_eq!(THEMED_RENDERS.swap(0, Ordering::SeqCst), 2);
+
+    // Both frames skip re-rendering, and so does what's beneath them...
+    window.render();
+    assert_eq!(THEMED_RENDERS.swap(0, Ordering::SeqCst), 0);
+
+    // ...unless it read a value that's since changed.
+    scene.set(themed(&Arc::new(Theme("dark"))));
+    window.render();
+    assert_eq!(names(), vec![Some("dark".to_string()), Some("dark".to_string())]);
+    assert_eq!(THEMED_RENDERS.swap(0, Ordering::SeqCst), 2);
+}
+
+#[test]
+fn refs_resolve_once_mounted_and_clear_once_unmounted() {
+    let (window, scene) = common::window();
+    let label = Ref::new();
+    let greeting = Ref::new();
+    assert!(label.key().is_none());
+
+    let (label_ref, greeting_ref) = (label.clone(), greeting.clone());
+    scene.set(move || rsx! {
+        <View styles=["box"]>
+            <Text ref={label_ref.clone()} styles=["row"]>{text!("{}", "hello")}</Text>
+            <Label ref={greeting_ref.clone()} label={"hi".to_string()} />
+        </View>
+    });
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    let text = content.children()[0].children()[0].clone();
+    assert_eq!(label.native_node(), Some(text));
+    assert_eq!(label.layout().map(|layout| layout.size.width), Some(100.));
+
+    // Refs to `Component`s without a native node of their own still reach the `Component`.
+    assert!(greeting.native_node().is_none());
+    let greeted = greeting.with_component(|component| {
+        component.as_any().downcast_ref::<Label>().unwrap().props.label.clone()
+    });
+    assert_eq!(greeted, Some("hi".to_string()));
+
+    scene.set(|| rsx! { <View styles=["box"] /> });
+    window.render();
+    assert!(label.key().is_none());
+    assert!(label.native_node().is_none());
+    assert!(greeting.with_component(|_| ()).is_none());
+}
+
+#[test]
+fn portals_attach_under_their_target_in_the_same_engine_only() {
+    let (engine, root) = common::engine();
+    let (_, elsewhere) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    let portal = |target: ComponentKey| rsx! {
+        <View styles=["box"]>
+            <Boundary>
+                <Portal target={Some(target)}><View styles=["row"] /></Portal>
+            </Boundary>
+        </View>
+    };
+
+    engine.diff_and_render_root(root, (600., 400.), portal(root)).unwrap();
+    assert_eq!(root_node.children().len(), 2);
+    assert_eq!(root_node.children()[1].size().width, 100.);
+
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), portal(elsewhere)).unwrap();
+    assert_eq!(root_node.children().len(), 1);
+    let fallback = root_node.children()[0].children()[0].text().unwrap();
+    assert!(fallback.contains("another RenderEngine"));
+}
+
+fn highlighted_rows(highlighted: &str) -> RSX {
+    rsx! {
+        <View styles=["box"]>
+            {["a", "b", "c"].iter().map(|label| {
+                let styles = if *label == highlighted { "highlighted" } else { "row" };
+                rsx! { <Text key={label.to_string()} styles=[styles]>{text!("{}", label)}</Text> }
+            })}
+        </View>
+    }
+}
+
+#[test]
+fn only_changed_nodes_are_restyled_and_laid_out_again() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), highlighted_rows("")).unwrap();
+    let boxed = root_node.children()[0].clone();
+    let counts = || -> Vec<usize> {
+        let mut counts = vec![root_node.styles_applied(), boxed.styles_applied()];
+        counts.extend(boxed.children().iter().map(|row| row.styles_applied()));
+        counts
+    };
+
+    // Rendering the same thing again doesn't touch anything.
+    let before = counts();
+    engine.diff_and_render_root(root, (600., 400.), highlighted_rows("")).unwrap();
+    assert_eq!(counts(), before);
+
+    engine.diff_and_render_root(root, (600., 400.), highlighted_rows("b")).unwrap();
+    let after = counts();
+    assert_eq!(boxed.children()[1].appearance().background_color, Color::new(0, 255, 0, 255));
+    assert!(after[3] > before[3]);
+    assert_eq!((after[0], after[1], after[2], after[4]), (before[0], before[1], before[2], before[4]));
+}
+
+#[test]
+fn text_is_sized_to_fit_its_contents() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["stack"]><Text styles=["label"]>{text!("{}", "hello")}</Text></View>
+    }).unwrap();
+
+    // The headless backend measures 20pt text at 10pt a character, and 24pt a line.
+    let label = root_node.children()[0].children()[0].clone();
+    assert_eq!((label.size().width, label.size().height), (50., 24.));
+}
+
+static MEASURES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct MeasuredProps {}
+
+/// A natively backed `Component` that counts how often it's asked for a measure function.
+#[derive(Props)]
+struct Measured {
+    node: NativeNode
+}
+
+impl Component for Measured {
+    fn new(_: ComponentKey) -> Measured {
+        Measured { node: NativeNode::new("Measured") }
+    }
+
+    fn has_native_backing_node(&self) -> bool {
+        true
+    }
+
+    fn borrow_native_backing_node(&self) -> Option<NativeNode> {
+        Some(self.node.clone())
+    }
+
+    fn measure_func(&self, _: &Appearance) -> Option<MeasureFunc> {
+        MEASURES.fetch_add(1, Ordering::SeqCst);
+        Some(Box::new(|_| Ok(Size { width: 10., height: 10. })))
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(RSX::None)
+    }
+}
+
+#[test]
+fn measure_functions_are_only_replaced_when_text_or_font_changes() {
+    let (engine, root) = common::engine();
+    let measured = |label: &str, styles: &str| rsx! {
+        <View styles=["box"]><Measured styles=[styles]>{text!("{}", label)}</Measured></View>
+    };
+
+    engine.diff_and_render_root(root, (600., 400.), measured("a", "row")).unwrap();
+    assert_eq!(MEASURES.swap(0, Ordering::SeqCst), 1);
+
+    engine.diff_and_render_root(root, (600., 400.), measured("a", "row")).unwrap();
+    assert_eq!(MEASURES.swap(0, Ordering::SeqCst), 0);
+
+    engine.diff_and_render_root(root, (600., 400.), measured("b", "row")).unwrap();
+    assert_eq!(MEASURES.swap(0, Ordering::SeqCst), 1);
+
+    engine.diff_and_render_root(root, (600., 400.), measured("b", "heading")).unwrap();
+    assert_eq!(MEASURES.swap(0, Ordering::SeqCst), 1);
+}
+
+#[test]
+fn native_children_follow_inserts_removals_and_moves() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+    let texts = || -> Vec<Option<String>> {
+        root_node.children()[0].children().iter().map(|node| node.text()).collect()
+    };
+
+    engine.diff_and_render_root(root, (600., 400.), rows(&["a", "b", "c"])).unwrap();
+    let b = root_node.children()[0].children()[1].clone();
+
+    engine.diff_and_render_root(root, (600., 400.), rows(&["a", "x", "c", "b"])).unwrap();
+    assert_eq!(texts(), vec![Some("a".into()), Some("x".into()), Some("c".into()), Some("b".into())]);
+    assert_eq!(root_node.children()[0].children()[3], b);
+
+    engine.diff_and_render_root(root, (600., 400.), rows(&["c", "a"])).unwrap();
+    assert_eq!(texts(), vec![Some("c".into()), Some("a".into())]);
+    assert!(b.parent().is_none());
+
+    // Anything a Portal attached elsewhere comes off with it.
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["box"]>
+            <Portal target={Some(root)}><View styles=["row"] /></Portal>
+        </View>
+    }).unwrap();
+    assert_eq!(root_node.children().len(), 2);
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <View styles=["box"] /> }).unwrap();
+    assert_eq!(root_node.children().len(), 1);
+}
+
+#[test]
+fn inspecting_dumps_tags_styles_and_layouts() {
+    let (engine, root) = common::engine();
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["stack"]>
+            <Label styles=["label"] label={"hi".to_string()} />
+            <View styles=["highlighted"] />
+        </View>
+    }).unwrap();
+
+    let tree = engine.inspect(root).unwrap();
+    assert_eq!(tree.to_string(), concat!(
+        "root styles=[root] native (0, 0) 600x400\n",
+        "  style: size={\"height\":{\"Points\":400.0},\"width\":{\"Points\":600.0}}\n",
+        "    View styles=[stack] native (0, 0) 300x100\n",
+        "      style: align_items=\"FlexStart\", flex_direction=\"Column\", size={\"height\":{\"Points\":100.0},\"width\":{\"Points\":300.0}}\n",
+        "        Label styles=[label]\n",
+        "            Text styles=[row] \"hi\" native (0, 0) 100x20\n",
+        "              style: size={\"height\":{\"Points\":20.0},\"width\":{\"Points\":100.0}}\n",
+        "        View styles=[highlighted] native (0, 20) 100x20\n",
+        "          style: size={\"height\":{\"Points\":20.0},\"width\":{\"Points\":100.0}}\n",
+        "          appearance: background_color={\"alpha\":255,\"blue\":0,\"green\":255,\"red\":0}\n"
+    ));
+
+    let json: serde_json::Value = serde_json::from_str(&tree.to_json().unwrap()).unwrap();
+    let label = &json["children"][0]["children"][0];
+    assert_eq!(label["tag"], "Label");
+    assert_eq!(label["style_keys"], serde_json::json!(["label"]));
+    assert_eq!(label["is_native_backed"], false);
+    assert!(label["style"].is_null() && label["layout"].is_null());
+
+    let highlighted = &json["children"][0]["children"][1];
+    assert_eq!(highlighted["layout"]["location"], serde_json::json!({ "x": 0., "y": 20. }));
+    assert_eq!(highlighted["layout"]["size"], serde_json::json!({ "width": 100., "height": 20. }));
+    assert_eq!(highlighted["appearance"]["background_color"]["green"], 255);
+}
+
+static UNMOUNTED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct LeavingProps {}
+
+#[derive(Props)]
+struct Leaving;
+
+impl Component for Leaving {
+    fn new(_: ComponentKey) -> Leaving {
+        Leaving
+    }
+
+    fn component_will_unmount(&mut self) {
+        UNMOUNTED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <View styles=["row"] /> })
+    }
+}
+
+#[test]
+fn components_are_unmounted_when_their_removal_is_committed() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! {
+        <View styles=["box"]><Leaving /></View>
+    }).unwrap();
+
+    let patches = engine.diff_root(root, (600., 400.), rsx! { <View styles=["box"] /> }).unwrap();
+    assert_eq!(UNMOUNTED.load(Ordering::SeqCst), 0);
+    assert_eq!(root_node.children()[0].children().len(), 1);
+
+    engine.commit(patches).unwrap();
+    assert_eq!(UNMOUNTED.load(Ordering::SeqCst), 1);
+    assert!(root_node.children()[0].children().is_empty());
+}
@@ -0,0 +1,199 @@
+//! Queued state updates, including ones queued from lifecycle methods while committing.
+
+#![cfg(feature = "headless")]
+#![recursion_limit = "512"]
+
+mod common;
+
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use alchemy::{Component, ComponentKey, Error, Props, Ref, RenderEngine, rsx, RSX, RENDER_ENGINE, text, Text, View};
+
+#[derive(Clone, Default)]
+struct CounterProps {
+    mount_increments: i32,
+    update_increments: i32
+}
+
+/// Counts up once per update it's told to make on mounting, and then once per update it's told
+/// to make on updating.
+#[derive(Props)]
+struct Counter {
+    props: CounterProps,
+    key: ComponentKey,
+    count: i32
+}
+
+impl Component for Counter {
+    fn new(key: ComponentKey) -> Counter {
+        Counter { props: CounterProps::default(), key, count: 0 }
+    }
+
+    fn component_did_mount(&mut self) {
+        if self.props.mount_increments > 0 {
+            RENDER_ENGINE.queue_update_for(self.key, |counter: &mut Counter| counter.count += 1);
+        }
+    }
+
+    fn component_did_update(&mut self, _: &Any, _: Option<Box<Any + Send>>) {
+        if self.count < self.props.mount_increments + self.props.update_increments {
+            RENDER_ENGINE.queue_update_for(self.key, |counter: &mut Counter| counter.count += 1);
+        }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! { <Text styles=["row"]>{text!("count {}", self.count)}</Text> })
+    }
+}
+
+#[test]
+fn updates_queued_while_mounting_are_committed_before_rendering_returns() {
+    let (window, scene) = common::window();
+    scene.set(|| rsx! { <Counter mount_increments={1} /> });
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    assert_eq!(content.children()[0].text(), Some("count 1".to_string()));
+}
+
+#[test]
+fn updates_queued_while_updating_are_committed_before_flushing_returns() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    let counter = Ref::new();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter ref={counter.clone()} update_increments={3} /> }).unwrap();
+    engine.resize_root(root, (500., 400.)).unwrap();
+    assert_eq!(root_node.children()[0].text(), Some("count 0".to_string()));
+
+    engine.queue_update_for(counter.key().unwrap(), |counter: &mut Counter| counter.count += 1);
+    engine.flush_queued_updates().unwrap();
+    assert_eq!(root_node.children()[0].text(), Some("count 3".to_string()));
+}
+
+#[test]
+fn updates_that_never_settle_are_cut_off() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    let counter = Ref::new();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter ref={counter.clone()} update_increments={1000} /> }).unwrap();
+    engine.queue_update_for(counter.key().unwrap(), |counter: &mut Counter| counter.count += 1);
+    assert!(engine.flush_queued_updates().is_err());
+
+    // Whatever was committed before giving up stays committed, and nothing is left queued.
+    let text = root_node.children()[0].text().unwrap();
+    assert!(text != "count 0" && text != "count 1000");
+    engine.flush_queued_updates().unwrap();
+    assert_eq!(root_node.children()[0].text().unwrap(), text);
+}
+
+static ASKED_TO_UPDATE: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Default)]
+struct StubbornProps {}
+
+/// Never agrees to update, and relabels itself (forcing a re-render) once it's mounted.
+#[derive(Props)]
+struct Stubborn {
+    key: ComponentKey,
+    label: &'static str
+}
+
+impl Component for Stubborn {
+    fn new(key: ComponentKey) -> Stubborn {
+        Stubborn { key, label: "mounted" }
+    }
+
+    fn should_component_update(&self) -> bool {
+        ASKED_TO_UPDATE.fetch_add(1, Ordering::SeqCst);
+        false
+    }
+
+    fn component_did_mount(&mut self) {
+        self.label = "forced";
+        self.force_update(self.key).unwrap();
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <View styles=["box"]>
+                <Text styles=["row"]>{text!("{}", self.label)}</Text>
+            </View>
+        })
+    }
+}
+
+#[test]
+fn forcing_an_update_while_committing_re_renders_the_subtree() {
+    let (window, scene) = common::window();
+    scene.set(|| rsx! { <Stubborn /> });
+    window.show();
+
+    let content = window.borrow_native_backing_node();
+    assert_eq!(content.children()[0].children()[0].text(), Some("forced".to_string()));
+    assert_eq!(ASKED_TO_UPDATE.load(Ordering::SeqCst), 0);
+}
+
+#[derive(Clone, Default)]
+struct ImpatientProps {}
+
+/// Forces a re-render of itself from another thread the first time it renders, while the render
+/// that's underway still holds the tree.
+#[derive(Props)]
+struct Impatient {
+    key: ComponentKey,
+    renders: AtomicUsize
+}
+
+impl Component for Impatient {
+    fn new(key: ComponentKey) -> Impatient {
+        Impatient { key, renders: AtomicUsize::new(0) }
+    }
+
+    fn render(&self, _: Vec<RSX>) -> Result<RSX, Error> {
+        let renders = self.renders.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if renders == 1 {
+            let key = self.key;
+            thread::spawn(move || RENDER_ENGINE.force_update(key).unwrap()).join().unwrap();
+        }
+
+        Ok(rsx! { <Text styles=["row"]>{text!("renders {}", renders)}</Text> })
+    }
+}
+
+#[test]
+fn updates_forced_from_another_thread_mid_render_arent_stranded() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Impatient /> }).unwrap();
+    assert_eq!(root_node.children()[0].text(), Some("renders 2".to_string()));
+
+    // Nothing's left in the queue for whoever flushes next.
+    engine.flush_queued_updates().unwrap();
+    assert_eq!(root_node.children()[0].text(), Some("renders 2".to_string()));
+}
+
+#[test]
+fn updates_through_the_shared_engine_reach_the_owning_engine() {
+    let (engine, root) = common::engine();
+    let root_node = engine.native_node_for(root).unwrap().unwrap();
+
+    let counter = Ref::new();
+
+    engine.diff_and_render_root(root, (600., 400.), rsx! { <Counter ref={counter.clone()} /> }).unwrap();
+    RENDER_ENGINE.queue_update_for(counter.key().unwrap(), |counter: &mut Counter| counter.count += 1);
+    engine.flush_queued_updates().unwrap();
+    assert_eq!(root_node.children()[0].text(), Some("count 1".to_string()));
+
+    // Once the engine's gone, its keys don't route anywhere.
+    assert!(RenderEngine::for_key(root).is_some());
+    drop(engine);
+    assert!(RenderEngine::for_key(root).is_none());
+}
@@ -0,0 +1,59 @@
+//! Forwards `AccessibilityProperties` to the `NSAccessibility` properties on a view, so that
+//! VoiceOver and friends can describe it.
+
+use objc_id::Id;
+use objc::{msg_send, sel, sel_impl};
+use objc::runtime::Object;
+
+use cocoa::base::{id, nil, YES};
+use cocoa::foundation::NSString;
+
+use alchemy_lifecycle::accessibility::{AccessibilityProperties, AccessibilityRole};
+
+/// Sets the role, label, hint and value on `view`, and marks it as an accessibility element.
+/// Properties that aren't set are cleared.
+pub fn set_accessibility(view: &Id<Object>, properties: &AccessibilityProperties) {
+    unsafe {
+        let role = NSString::alloc(nil).init_str(ns_accessibility_role(properties.role));
+        let label = ns_string_or_nil(&properties.label);
+        let hint = ns_string_or_nil(&properties.hint);
+        let value = ns_string_or_nil(&properties.value);
+
+        msg_send![&**view, setAccessibilityElement:YES];
+        msg_send![&**view, setAccessibilityRole:role];
+        msg_send![&**view, setAccessibilityLabel:label];
+        msg_send![&**view, setAccessibilityHelp:hint];
+        msg_send![&**view, setAccessibilityValue:value];
+
+        // The view copies these, so ours can go. Messaging nil is a no-op.
+        msg_send![role, release];
+        msg_send![label, release];
+        msg_send![hint, release];
+        msg_send![value, release];
+    }
+}
+
+/// Maps an `AccessibilityRole` to the `NSAccessibilityRole` constant it corresponds to.
+pub fn ns_accessibility_role(role: AccessibilityRole) -> &'static str {
+    match role {
+        AccessibilityRole::Group => "AXGroup",
+        AccessibilityRole::Text => "AXStaticText",
+        AccessibilityRole::Button => "AXButton",
+        AccessibilityRole::Link => "AXLink",
+        AccessibilityRole::Image => "AXImage",
+        AccessibilityRole::Heading => "AXHeading",
+        AccessibilityRole::TextBox => "AXTextField",
+        AccessibilityRole::CheckBox => "AXCheckBox",
+        AccessibilityRole::List => "AXList",
+        AccessibilityRole::ListItem => "AXGroup"
+    }
+}
+
+/// Allocates an `NSString` for `value`, or returns `nil` if there isn't one. The caller owns
+/// (and has to release) the result.
+unsafe fn ns_string_or_nil(value: &Option<String>) -> id {
+    match value {
+        Some(value) => NSString::alloc(nil).init_str(value),
+        None => nil
+    }
+}
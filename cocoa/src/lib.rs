@@ -17,6 +17,7 @@
 //!
 //! [coc]: https://www.contributor-covenant.org/version/1/4/code-of-conduct
 
+pub mod accessibility;
 pub mod color;
 pub mod app;
 pub mod text;
@@ -4,17 +4,20 @@
 use std::sync::{Once, ONCE_INIT};
 
 use objc_id::{Id, ShareId};
-use objc::{msg_send, sel, sel_impl};
+use objc::{class, msg_send, sel, sel_impl};
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, BOOL};
 
 use cocoa::base::{id, nil, YES, NO};
 use cocoa::foundation::{NSRect, NSPoint, NSSize, NSString};
 
+use crate::accessibility::set_accessibility;
 use crate::color::IntoNSColor;
 
 use alchemy_styles::{Color, Layout, Appearance};
+use alchemy_styles::styles::{MeasureFunc, Number, Size};
 
+use alchemy_lifecycle::accessibility::AccessibilityProperties;
 use alchemy_lifecycle::traits::PlatformSpecificNodeType;
 
 static ALCHEMY_DELEGATE: &str = "alchemyDelegate";
@@ -90,6 +93,46 @@ impl Text {
         }
     }
 
+    /// Returns a function that measures the current text with the system font, at the size
+    /// given in `appearance`, wrapping to fit the available width.
+    pub fn measure_func(&self, appearance: &Appearance) -> MeasureFunc {
+        let text = self.text.clone();
+        let font_size = appearance.font_size;
+
+        Box::new(move |available: Size<Number>| {
+            let max_width = match available.width {
+                Number::Defined(width) => width.into(),
+                Number::Undefined => std::f64::MAX
+            };
+
+            let size = unsafe {
+                let string = NSString::alloc(nil).init_str(&text);
+                let font: id = msg_send![class!(NSFont), systemFontOfSize:font_size as f64];
+                let font_key = NSString::alloc(nil).init_str("NSFont");
+                let attributes: id = msg_send![class!(NSDictionary), dictionaryWithObject:font forKey:font_key];
+
+                // 1 == NSStringDrawingUsesLineFragmentOrigin, which enables wrapping.
+                let bounds = NSSize::new(max_width, std::f64::MAX);
+                let rect: NSRect = msg_send![string, boundingRectWithSize:bounds options:1 attributes:attributes];
+
+                // The dictionary keeps its own copy of the key, and is autoreleased itself.
+                msg_send![string, release];
+                msg_send![font_key, release];
+                rect.size
+            };
+
+            Ok(Size {
+                width: size.width.ceil() as f32,
+                height: size.height.ceil() as f32
+            })
+        })
+    }
+
+    /// Forwards accessibility properties to the underlying `NSTextField`.
+    pub fn set_accessibility(&mut self, properties: &AccessibilityProperties) {
+        set_accessibility(&self.inner_mut, properties);
+    }
+
     pub fn set_text(&mut self, text: String) {
         self.text = text;
     }
@@ -98,6 +141,7 @@ impl Text {
         unsafe {
             let string_value = NSString::alloc(nil).init_str(&self.text);
             msg_send![&*self.inner_mut, setStringValue:string_value];
+            msg_send![string_value, release];
         }
     }
 }
@@ -8,13 +8,21 @@ use objc::{msg_send, sel, sel_impl};
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, BOOL};
 
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
 use cocoa::base::{id, nil, YES};
-use cocoa::foundation::{NSRect, NSPoint, NSSize};
+use cocoa::foundation::{NSRect, NSPoint, NSSize, NSUInteger};
 
+use crate::accessibility::set_accessibility;
 use crate::color::IntoNSColor;
 
 use alchemy_styles::{Appearance, Color, Layout};
+use alchemy_styles::styles::Point;
 
+use alchemy_lifecycle::RENDER_ENGINE;
+use alchemy_lifecycle::accessibility::AccessibilityProperties;
+use alchemy_lifecycle::events::{Event, KeyboardEvent, Modifiers, MouseButton, MouseEvent};
 use alchemy_lifecycle::traits::PlatformSpecificNodeType;
 
 static ALCHEMY_DELEGATE: &str = "alchemyDelegate";
@@ -41,6 +49,16 @@ impl View {
             let view: id = msg_send![alloc, initWithFrame:rect_zero];
             msg_send![view, setWantsLayer:YES];
             msg_send![view, setLayerContentsRedrawPolicy:1];
+
+            // NSView only hears about the mouse entering, leaving and moving through a tracking
+            // area. NSTrackingMouseEnteredAndExited | NSTrackingMouseMoved |
+            // NSTrackingActiveInKeyWindow | NSTrackingInVisibleRect (which keeps it sized to the
+            // view, so the rect here doesn't matter).
+            let options: NSUInteger = 0x01 | 0x02 | 0x20 | 0x200;
+            let tracking_area: id = msg_send![Class::get("NSTrackingArea").unwrap(), alloc];
+            let tracking_area: id = msg_send![tracking_area, initWithRect:rect_zero options:options owner:view userInfo:nil];
+            msg_send![view, addTrackingArea:tracking_area];
+            msg_send![tracking_area, release];
             let x = view.clone();
             (Id::from_ptr(view), ShareId::from_ptr(x))
         };
@@ -65,6 +83,50 @@ impl View {
         }
     }
 
+    /// Inserts a child NSView (or subclassed type) at `index` among this view's subviews, or
+    /// appends it if `index` is past the end. If it's already a subview, it's moved.
+    pub fn insert_child(&mut self, child: PlatformSpecificNodeType, index: usize) {
+        unsafe {
+            let this: id = &*self.inner_mut as *const Object as id;
+
+            // Take it out first, so that `index` lines up with where it's going.
+            let superview: id = msg_send![&*child, superview];
+            if superview == this {
+                msg_send![&*child, removeFromSuperview];
+            }
+
+            let subviews: id = msg_send![this, subviews];
+            let count: usize = msg_send![subviews, count];
+
+            if index >= count {
+                msg_send![this, addSubview:child];
+            } else {
+                // -1 == NSWindowBelow
+                let sibling: id = msg_send![subviews, objectAtIndex:index];
+                msg_send![this, addSubview:child positioned:-1isize relativeTo:sibling];
+            }
+        }
+    }
+
+    /// Removes a child NSView (or subclassed type) from this view, if it's a subview.
+    pub fn remove_child(&mut self, child: PlatformSpecificNodeType) {
+        unsafe {
+            let this: id = &*self.inner_mut as *const Object as id;
+            let superview: id = msg_send![&*child, superview];
+
+            if superview == this {
+                msg_send![&*child, removeFromSuperview];
+            }
+        }
+    }
+
+    /// Swaps a child NSView (or subclassed type) out for another, in the same position.
+    pub fn replace_child(&mut self, old: PlatformSpecificNodeType, new: PlatformSpecificNodeType) {
+        unsafe {
+            msg_send![&*self.inner_mut, replaceSubview:old with:new];
+        }
+    }
+
     /// Given a `&Style`, will set the frame, background color, borders and so forth. It then
     /// calls `setNeedsDisplay:YES` on the Objective-C side, so that Cocoa will re-render this
     /// view.
@@ -82,6 +144,11 @@ impl View {
             msg_send![&*self.inner_mut, setNeedsDisplay:YES];
         }
     }
+
+    /// Forwards accessibility properties to the underlying `NSView`.
+    pub fn set_accessibility(&mut self, properties: &AccessibilityProperties) {
+        set_accessibility(&self.inner_mut, properties);
+    }
 }
 
 /// This is used for some specific calls, where macOS NSView needs to be
@@ -103,6 +170,97 @@ extern fn update_layer(this: &Object, _: Sel) {
     }
 }
 
+/// Converts the modifier flags on an `NSEvent` into `Modifiers`.
+unsafe fn modifiers_for(event: id) -> Modifiers {
+    let flags: NSUInteger = msg_send![event, modifierFlags];
+
+    Modifiers {
+        shift: flags & (1 << 17) != 0,
+        control: flags & (1 << 18) != 0,
+        alt: flags & (1 << 19) != 0,
+        command: flags & (1 << 20) != 0
+    }
+}
+
+/// Converts a mouse `NSEvent` into a `MouseEvent`, with the location relative to `this`.
+unsafe fn mouse_event_for(this: &Object, event: id, button: MouseButton) -> MouseEvent {
+    let location: NSPoint = msg_send![event, locationInWindow];
+    let location: NSPoint = msg_send![this, convertPoint:location fromView:nil];
+
+    MouseEvent::new(Point { x: location.x as f32, y: location.y as f32 }, button, modifiers_for(event))
+}
+
+/// Converts a key `NSEvent` into a `KeyboardEvent`.
+unsafe fn keyboard_event_for(event: id) -> KeyboardEvent {
+    let characters: id = msg_send![event, characters];
+    let characters: *const c_char = msg_send![characters, UTF8String];
+
+    KeyboardEvent::new(CStr::from_ptr(characters).to_string_lossy().into_owned(), modifiers_for(event))
+}
+
+/// Reports an event against `this`, for the reconciler to route to a handler.
+fn dispatch(this: &Object, event: Event) {
+    let node = unsafe { ShareId::from_ptr(this as *const Object as id) };
+
+    if let Err(e) = RENDER_ENGINE.dispatch_event(&node, event) {
+        eprintln!("Error dispatching an event: {}", e);
+    }
+}
+
+/// Fires when the view receives a `mouseDown:` event.
+extern fn mouse_down(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Left) };
+    dispatch(this, Event::MouseDown(event));
+}
+
+/// Fires when the view receives a `mouseUp:` event.
+extern fn mouse_up(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Left) };
+    dispatch(this, Event::MouseUp(event));
+}
+
+/// Fires when the view receives a `rightMouseDown:` event.
+extern fn right_mouse_down(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Right) };
+    dispatch(this, Event::MouseDown(event));
+}
+
+/// Fires when the view receives a `rightMouseUp:` event.
+extern fn right_mouse_up(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Right) };
+    dispatch(this, Event::MouseUp(event));
+}
+
+/// Fires when the view receives a `mouseMoved:` event.
+extern fn mouse_moved(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Left) };
+    dispatch(this, Event::MouseMove(event));
+}
+
+/// Fires when the view receives a `mouseEntered:` event.
+extern fn mouse_entered(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Left) };
+    dispatch(this, Event::MouseEnter(event));
+}
+
+/// Fires when the view receives a `mouseExited:` event.
+extern fn mouse_exited(this: &Object, _: Sel, event: id) {
+    let event = unsafe { mouse_event_for(this, event, MouseButton::Left) };
+    dispatch(this, Event::MouseLeave(event));
+}
+
+/// Fires when the view receives a `keyDown:` event.
+extern fn key_down(this: &Object, _: Sel, event: id) {
+    let event = unsafe { keyboard_event_for(event) };
+    dispatch(this, Event::KeyDown(event));
+}
+
+/// Fires when the view receives a `keyUp:` event.
+extern fn key_up(this: &Object, _: Sel, event: id) {
+    let event = unsafe { keyboard_event_for(event) };
+    dispatch(this, Event::KeyUp(event));
+}
+
 /// Registers an `NSView` subclass, and configures it to hold some ivars for various things we need
 /// to store.
 fn register_class() -> *const Class {
@@ -124,7 +282,16 @@ fn register_class() -> *const Class {
         decl.add_method(sel!(wantsUpdateLayer), enforce_normalcy as extern fn(&Object, _) -> BOOL);
 
         // Ensure mouse events and so on work
-        //decl.add_method(sel!(acceptsFirstResponder), update_layer as extern fn(&Object, _));
+        decl.add_method(sel!(acceptsFirstResponder), enforce_normalcy as extern fn(&Object, _) -> BOOL);
+        decl.add_method(sel!(mouseDown:), mouse_down as extern fn(&Object, _, _));
+        decl.add_method(sel!(mouseUp:), mouse_up as extern fn(&Object, _, _));
+        decl.add_method(sel!(rightMouseDown:), right_mouse_down as extern fn(&Object, _, _));
+        decl.add_method(sel!(rightMouseUp:), right_mouse_up as extern fn(&Object, _, _));
+        decl.add_method(sel!(mouseMoved:), mouse_moved as extern fn(&Object, _, _));
+        decl.add_method(sel!(mouseEntered:), mouse_entered as extern fn(&Object, _, _));
+        decl.add_method(sel!(mouseExited:), mouse_exited as extern fn(&Object, _, _));
+        decl.add_method(sel!(keyDown:), key_down as extern fn(&Object, _, _));
+        decl.add_method(sel!(keyUp:), key_up as extern fn(&Object, _, _));
 
         // A pointer back to our View, for forwarding mouse + etc events.
         // Note that NSView's don't really have a "delegate", I'm just using it here
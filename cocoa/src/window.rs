@@ -13,7 +13,7 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{msg_send, sel, sel_impl};
 
-use alchemy_lifecycle::traits::{AppDelegate, Component};
+use alchemy_lifecycle::traits::{AppDelegate, PlatformSpecificNodeType};
 use alchemy_styles::Appearance;
 
 static APP_PTR: &str = "alchemyAppPtr";
@@ -75,6 +75,15 @@ impl Window {
         }
     }
 
+    /// Returns a pointer to the content view of this window. The pointer is not mutable; however,
+    /// you can send messages to it (unsafely).
+    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+        unsafe {
+            let content_view: id = msg_send![&*self.inner, contentView];
+            ShareId::from_ptr(content_view)
+        }
+    }
+
     pub fn set_title(&mut self, title: &str) {
         unsafe {
             let title = NSString::alloc(nil).init_str(title);
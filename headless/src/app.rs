@@ -0,0 +1,40 @@
+//! A stand-in for a platform application object. There's no run loop to block on
+//! when running headless, so `run()` simply walks the `AppDelegate` through the
+//! launch lifecycle and returns.
+
+use alchemy_lifecycle::traits::AppDelegate;
+
+/// A headless application. It holds a pointer back to the Alchemy shared application,
+/// along with a function that knows how to forward launch events to it.
+pub struct App {
+    app_ptr: usize,
+    launch: fn(usize)
+}
+
+impl App {
+    /// Stores the parent application pointer, for looping events back around later.
+    pub fn new<T: AppDelegate>(parent_app_ptr: *const T) -> Self {
+        App {
+            app_ptr: parent_app_ptr as usize,
+            launch: launch::<T>
+        }
+    }
+
+    /// Fires the launch lifecycle events on the parent application. Unlike other backends,
+    /// this does not block.
+    pub fn run(&self) {
+        (self.launch)(self.app_ptr);
+    }
+}
+
+/// Fires the events that a platform would normally send while an application launches and
+/// becomes active, in the same order they'd arrive in.
+fn launch<T: AppDelegate>(app_ptr: usize) {
+    unsafe {
+        let app = app_ptr as *mut T;
+        (*app).will_finish_launching();
+        (*app).did_finish_launching();
+        (*app).will_become_active();
+        (*app).did_become_active();
+    }
+}
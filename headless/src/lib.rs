@@ -0,0 +1,23 @@
+//! This crate provides a headless backend for Alchemy, the Rust GUI framework.
+//! Instead of native `NSView`s (or the like), views are backed by in-memory nodes
+//! that record frames, appearance, text and child order. There's no windowserver
+//! involved, so this is mostly useful for running and asserting on the render
+//! pipeline in environments that can't show a window (e.g, CI).
+//!
+//! # License
+//!
+//! Copyright 2018 Ryan McGrath. See the license files included in the root repository
+//! for more information, along with credit to applicable parties for who this project
+//! would not have happened.
+//!
+//! # Code of Conduct
+//!
+//! Please note that this project is released with a [Contributor Code of
+//! Conduct][coc]. By participating in this project you agree to abide by its terms.
+//!
+//! [coc]: https://www.contributor-covenant.org/version/1/4/code-of-conduct
+
+pub mod app;
+pub mod text;
+pub mod view;
+pub mod window;
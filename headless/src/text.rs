@@ -0,0 +1,101 @@
+//! Implements a headless Text bridge. Text is held until `render()` is called,
+//! at which point it's flushed to the backing node - the same flow other
+//! backends use for their label widgets.
+
+use alchemy_styles::{Appearance, Layout};
+use alchemy_styles::styles::{MeasureFunc, Number, Size};
+
+use alchemy_lifecycle::accessibility::AccessibilityProperties;
+use alchemy_lifecycle::headless::NativeNode;
+use alchemy_lifecycle::traits::PlatformSpecificNodeType;
+
+/// A wrapper for an in-memory `NativeNode`, standing in for a platform label.
+#[derive(Debug)]
+pub struct Text {
+    text: String,
+    inner: NativeNode
+}
+
+impl Default for Text {
+    fn default() -> Text {
+        Text::new()
+    }
+}
+
+impl Text {
+    /// Allocates a new, detached node with no text.
+    pub fn new() -> Text {
+        Text {
+            text: "".into(),
+            inner: NativeNode::new("Text")
+        }
+    }
+
+    /// Returns a handle to the underlying node.
+    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+        self.inner.clone()
+    }
+
+    /// Appends a child node to this text node.
+    pub fn append_child(&mut self, child: PlatformSpecificNodeType) {
+        self.inner.append_child(child);
+    }
+
+    /// Records the computed frame and appearance on the backing node.
+    pub fn apply_styles(&mut self, appearance: &Appearance, layout: &Layout) {
+        self.inner.set_frame(layout);
+        self.inner.set_appearance(appearance);
+    }
+
+    /// Records accessibility properties on the backing node.
+    pub fn set_accessibility(&mut self, properties: &AccessibilityProperties) {
+        self.inner.set_accessibility(properties);
+    }
+
+    /// Sets the text to be displayed on the next `render()`.
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+
+    /// Returns a function that measures the current text. There's no font engine behind this, so
+    /// it uses fixed metrics derived from the font size (each character is half as wide as the
+    /// font is tall, and lines are 1.2x the font size), wrapping at spaces to fit the available
+    /// width. That's enough to exercise intrinsic sizing deterministically.
+    pub fn measure_func(&self, appearance: &Appearance) -> MeasureFunc {
+        let text = self.text.clone();
+        let char_width = appearance.font_size * 0.5;
+        let line_height = (appearance.font_size * 1.2).ceil();
+
+        Box::new(move |available: Size<Number>| {
+            let max_chars = match available.width {
+                Number::Defined(width) => Some(((width / char_width).floor() as usize).max(1)),
+                Number::Undefined => None
+            };
+
+            let mut lines: Vec<usize> = vec![];
+            for word in text.split(' ') {
+                let length = word.chars().count();
+                let fits = |line: usize| match max_chars {
+                    Some(max_chars) => line + 1 + length <= max_chars,
+                    None => true
+                };
+
+                match lines.last_mut() {
+                    Some(line) if fits(*line) => { *line += 1 + length; },
+                    _ => { lines.push(length); }
+                }
+            }
+
+            let widest = lines.iter().cloned().max().unwrap_or(0);
+            Ok(Size {
+                width: widest as f32 * char_width,
+                height: lines.len() as f32 * line_height
+            })
+        })
+    }
+
+    /// Flushes the current text to the backing node.
+    pub fn render(&mut self) {
+        self.inner.set_text(&self.text);
+    }
+}
@@ -0,0 +1,65 @@
+//! Implements a headless View bridge. Rather than wrapping something like
+//! `NSView`, this records everything it's told on an in-memory `NativeNode`.
+
+use alchemy_styles::{Appearance, Layout};
+
+use alchemy_lifecycle::accessibility::AccessibilityProperties;
+use alchemy_lifecycle::headless::NativeNode;
+use alchemy_lifecycle::traits::PlatformSpecificNodeType;
+
+/// A wrapper for an in-memory `NativeNode`, standing in for a platform view.
+#[derive(Debug)]
+pub struct View {
+    inner: NativeNode
+}
+
+impl Default for View {
+    fn default() -> View {
+        View::new()
+    }
+}
+
+impl View {
+    /// Allocates a new, detached node.
+    pub fn new() -> View {
+        View {
+            inner: NativeNode::new("View")
+        }
+    }
+
+    /// Returns a handle to the underlying node.
+    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+        self.inner.clone()
+    }
+
+    /// Appends a child node to this view.
+    pub fn append_child(&mut self, child: PlatformSpecificNodeType) {
+        self.inner.append_child(child);
+    }
+
+    /// Inserts a child node at `index`, moving it if it's already a child of this view.
+    pub fn insert_child(&mut self, child: PlatformSpecificNodeType, index: usize) {
+        self.inner.insert_child(child, index);
+    }
+
+    /// Removes a child node from this view.
+    pub fn remove_child(&mut self, child: PlatformSpecificNodeType) {
+        self.inner.remove_child(&child);
+    }
+
+    /// Swaps a child node out for another, in the same position.
+    pub fn replace_child(&mut self, old: PlatformSpecificNodeType, new: PlatformSpecificNodeType) {
+        self.inner.replace_child(&old, new);
+    }
+
+    /// Records the computed frame and appearance on the backing node.
+    pub fn apply_styles(&mut self, appearance: &Appearance, layout: &Layout) {
+        self.inner.set_frame(layout);
+        self.inner.set_appearance(appearance);
+    }
+
+    /// Records accessibility properties on the backing node.
+    pub fn set_accessibility(&mut self, properties: &AccessibilityProperties) {
+        self.inner.set_accessibility(properties);
+    }
+}
@@ -0,0 +1,65 @@
+//! Implements a headless Window bridge. The window itself is just another
+//! `NativeNode`, with the content view attached as its only child.
+
+use alchemy_styles::Appearance;
+
+use alchemy_lifecycle::headless::NativeNode;
+use alchemy_lifecycle::traits::{AppDelegate, PlatformSpecificNodeType};
+
+/// A stand-in for a platform window. Tracks the title and visibility, and holds the
+/// content view that the render tree gets attached to.
+#[derive(Debug)]
+pub struct Window {
+    pub inner: NativeNode,
+    pub title: String,
+    pub visible: bool
+}
+
+impl Window {
+    /// Creates a new window node and attaches `content_view` to it.
+    pub fn new<T: AppDelegate>(_window_id: usize, content_view: PlatformSpecificNodeType, _app_ptr: *const T) -> Window {
+        let inner = NativeNode::new("Window");
+        inner.append_child(content_view);
+
+        Window {
+            inner,
+            title: "".into(),
+            visible: false
+        }
+    }
+
+    /// Returns a handle to the content view for this window.
+    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+        self.inner.children().remove(0)
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.into();
+        self.inner.set_text(title);
+    }
+
+    pub fn set_dimensions(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.inner.set_frame_values(x as f32, y as f32, width as f32, height as f32);
+    }
+
+    /// Records the window-level appearance on the window node.
+    pub fn apply_styles(&mut self, appearance: &Appearance) {
+        self.inner.set_appearance(appearance);
+    }
+
+    /// Marks the window as visible.
+    ///
+    /// You should never be calling this yourself, mind you - Alchemy core handles this for you.
+    pub fn show(&mut self) {
+        self.visible = true;
+    }
+
+    /// Marks the window as hidden. Unlike other backends, this doesn't loop back around to the
+    /// `AppDelegate`; Alchemy core has already unregistered the window by the time this is
+    /// called.
+    ///
+    /// You should never be calling this yourself, mind you - Alchemy core handles this for you.
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+}
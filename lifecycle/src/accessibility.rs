@@ -0,0 +1,165 @@
+//! Implements accessibility: the properties `Component`s describe themselves with (see
+//! `Component::accessibility_properties()`), and `AccessibilityNode`, the tree that the
+//! `RenderEngine` derives from them. Backends forward properties to the platform as they're set;
+//! the tree itself is platform independent, which makes it handy for automated audits:
+//!
+//! ```rust,ignore
+//! let tree = window.render_engine().accessibility_tree(window.render_key())?;
+//! for button in tree.find_all(|node| node.role == AccessibilityRole::Button) {
+//!     assert!(button.label.is_some(), "unlabeled button: {}", tree.to_json()?);
+//! }
+//! ```
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// What a `Component` is, as far as assistive technology is concerned.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessibilityRole {
+    Group,
+    Text,
+    Button,
+    Link,
+    Image,
+    Heading,
+    TextBox,
+    CheckBox,
+    List,
+    ListItem
+}
+
+impl FromStr for AccessibilityRole {
+    type Err = ParseRoleError;
+
+    /// Parses a role from its lowercase name (e.g, "button"), which is how they're written in
+    /// `rsx! {}` (`role="button"`).
+    fn from_str(s: &str) -> Result<AccessibilityRole, ParseRoleError> {
+        match s {
+            "group" => Ok(AccessibilityRole::Group),
+            "text" => Ok(AccessibilityRole::Text),
+            "button" => Ok(AccessibilityRole::Button),
+            "link" => Ok(AccessibilityRole::Link),
+            "image" => Ok(AccessibilityRole::Image),
+            "heading" => Ok(AccessibilityRole::Heading),
+            "textbox" => Ok(AccessibilityRole::TextBox),
+            "checkbox" => Ok(AccessibilityRole::CheckBox),
+            "list" => Ok(AccessibilityRole::List),
+            "listitem" => Ok(AccessibilityRole::ListItem),
+            _ => Err(ParseRoleError(s.to_string()))
+        }
+    }
+}
+
+/// Returned when parsing an `AccessibilityRole` from a name that isn't one.
+#[derive(Clone, Debug)]
+pub struct ParseRoleError(String);
+
+impl Display for ParseRoleError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "unknown accessibility role: {}", self.0)
+    }
+}
+
+impl Error for ParseRoleError {}
+
+/// How a `Component` describes itself to assistive technology.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AccessibilityProperties {
+    /// What this is.
+    pub role: AccessibilityRole,
+
+    /// A short name for this (e.g, "Save"). If this isn't set, any text passed as children is
+    /// used instead.
+    pub label: Option<String>,
+
+    /// Describes what happens on interacting with this (e.g, "Saves the current document").
+    pub hint: Option<String>,
+
+    /// The current value, for things that have one (e.g, the contents of a text box).
+    pub value: Option<String>
+}
+
+impl Default for AccessibilityProperties {
+    /// A group, with nothing else set.
+    fn default() -> AccessibilityProperties {
+        AccessibilityProperties {
+            role: AccessibilityRole::Group,
+            label: None,
+            hint: None,
+            value: None
+        }
+    }
+}
+
+/// Where a node sits, relative to the root of the tree it's in.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct Frame {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32
+}
+
+/// A single node in an accessibility tree, as derived by `RenderEngine::accessibility_tree()`.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccessibilityNode {
+    /// The tag of the `Component` this node is for (e.g, "View").
+    pub tag: &'static str,
+
+    /// What this node is.
+    pub role: AccessibilityRole,
+
+    /// The name of this node - either its label, or the text passed to it.
+    pub label: Option<String>,
+
+    /// Describes what happens on interacting with this node.
+    pub hint: Option<String>,
+
+    /// The current value of this node, if it has one.
+    pub value: Option<String>,
+
+    /// The computed frame for this node. Only natively backed `Component`s have one.
+    pub frame: Option<Frame>,
+
+    /// Whether this node can take keyboard focus.
+    pub is_focusable: bool,
+
+    /// Whether this node has keyboard focus.
+    pub is_focused: bool,
+
+    /// The children of this node, in order.
+    pub children: Vec<AccessibilityNode>
+}
+
+impl AccessibilityNode {
+    /// Returns this tree as (pretty-printed) JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Returns every node in this tree (including this one) that satisfies `predicate`, in
+    /// depth-first order.
+    pub fn find_all<F: Fn(&AccessibilityNode) -> bool>(&self, predicate: F) -> Vec<&AccessibilityNode> {
+        fn walk<'a, F: Fn(&AccessibilityNode) -> bool>(
+            node: &'a AccessibilityNode,
+            predicate: &F,
+            found: &mut Vec<&'a AccessibilityNode>
+        ) {
+            if predicate(node) {
+                found.push(node);
+            }
+
+            for child in &node.children {
+                walk(child, predicate, found);
+            }
+        }
+
+        let mut found = vec![];
+        walk(self, &predicate, &mut found);
+        found
+    }
+}
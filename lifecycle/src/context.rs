@@ -0,0 +1,40 @@
+//! Implements `Context`, which is how values (e.g, a theme name, a locale, or a handle to some
+//! shared service) get passed down the `Component` tree without threading them through every
+//! `render()` call.
+//!
+//! A `Component` provides a value by returning it from `provide_context()`. Any descendant can
+//! then read it in `receive_context()`, which the reconciler calls before every render. If a
+//! provider hands back a different value (as in, a different `Arc`), any descendants that read
+//! the old one are re-rendered - even if they'd otherwise have been skipped.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// The values provided by the ancestors of a `Component`, nearest first.
+pub struct Context {
+    values: Vec<Arc<Any + Send + Sync>>,
+    consumed: RefCell<Vec<Arc<Any + Send + Sync>>>
+}
+
+impl Context {
+    pub(crate) fn new(values: Vec<Arc<Any + Send + Sync>>) -> Context {
+        Context {
+            values,
+            consumed: RefCell::new(vec![])
+        }
+    }
+
+    /// Returns the value of type `T` provided by the nearest ancestor, if there is one. Reading
+    /// a value subscribes the reading `Component` to changes in it.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        let value = self.values.iter().find(|value| value.is::<T>())?.clone();
+        self.consumed.borrow_mut().push(value.clone());
+        value.downcast::<T>().ok()
+    }
+
+    /// Returns the values that were read from this `Context`.
+    pub(crate) fn into_consumed(self) -> Vec<Arc<Any + Send + Sync>> {
+        self.consumed.into_inner()
+    }
+}
@@ -5,3 +5,11 @@
 /// A generic Error type that we use. It currently just aliases to `Box<std::error::Error>`,
 /// but could change in the future.
 pub type Error = Box<std::error::Error>;
+
+/// Extra information about an error caught by an error boundary, passed to
+/// `Component::component_did_catch()`.
+#[derive(Clone, Debug)]
+pub struct ErrorInfo {
+    /// The tags of the components from the root down to (and including) the one that failed.
+    pub component_stack: Vec<&'static str>
+}
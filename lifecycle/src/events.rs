@@ -0,0 +1,266 @@
+//! Implements events, and the handlers that `Component`s register for them. Handlers are set
+//! through attributes in `rsx! {}`:
+//!
+//! ```rust,ignore
+//! <View onClick={move |event: &MouseEvent| println!("Clicked at {:?}", event.location)} />
+//! ```
+//!
+//! Backends report raw input against the native node it happened on (see
+//! `RenderEngine::dispatch_event()`), or against whatever `RenderEngine::hit_test()` finds under
+//! a point. From there, events propagate much like they do in the DOM: down from the root
+//! through capture handlers (e.g, `onClickCapture`), and then back up from the target through
+//! regular handlers (e.g, `onClick`), until a handler calls `stop_propagation()`. Entering and
+//! leaving are specific to the node they happened on, so they only fire there.
+//!
+//! Clicks aren't reported by backends; they're synthesized when a mouse down and mouse up land
+//! on the same node.
+//!
+//! Keyboard events target whichever `Component` has focus in the window (falling back to the
+//! node they were reported against). Focus moves on mouse down and on Tab/Shift-Tab, unless a
+//! handler stops the event from propagating first - which is how a `Component` can keep Tab to
+//! itself.
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use alchemy_styles::styles::Point;
+use serde::{Deserialize, Serialize};
+
+use crate::traits::PlatformSpecificNodeType;
+
+/// Which mouse button an event came from.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Other
+}
+
+/// The modifier keys held down when an event happened.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub command: bool
+}
+
+/// A mouse event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MouseEvent {
+    /// Where the event happened, relative to the native node it was reported against.
+    pub location: Point<f32>,
+
+    /// The button involved. This is only meaningful for mouse downs and ups (and clicks).
+    pub button: MouseButton,
+
+    /// The modifier keys held down.
+    pub modifiers: Modifiers,
+
+    #[serde(skip)]
+    propagation_stopped: Cell<bool>
+}
+
+impl MouseEvent {
+    /// Creates a new `MouseEvent`.
+    pub fn new(location: Point<f32>, button: MouseButton, modifiers: Modifiers) -> MouseEvent {
+        MouseEvent {
+            location,
+            button,
+            modifiers,
+            propagation_stopped: Cell::new(false)
+        }
+    }
+
+    /// Stops this event from reaching any handlers further along its path.
+    pub fn stop_propagation(&self) {
+        self.propagation_stopped.set(true);
+    }
+
+    /// Returns whether a handler has stopped this event from propagating.
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped.get()
+    }
+}
+
+/// A keyboard event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyboardEvent {
+    /// The characters the key produced (e.g, "a", or "A" with shift held).
+    pub key: String,
+
+    /// The modifier keys held down.
+    pub modifiers: Modifiers,
+
+    #[serde(skip)]
+    propagation_stopped: Cell<bool>
+}
+
+impl KeyboardEvent {
+    /// Creates a new `KeyboardEvent`.
+    pub fn new(key: String, modifiers: Modifiers) -> KeyboardEvent {
+        KeyboardEvent {
+            key,
+            modifiers,
+            propagation_stopped: Cell::new(false)
+        }
+    }
+
+    /// Stops this event from reaching any handlers further along its path.
+    pub fn stop_propagation(&self) {
+        self.propagation_stopped.set(true);
+    }
+
+    /// Returns whether a handler has stopped this event from propagating.
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped.get()
+    }
+}
+
+/// An event, as reported by a backend (or synthesized by the reconciler, in the case of
+/// `Click`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    Click(MouseEvent),
+    MouseDown(MouseEvent),
+    MouseUp(MouseEvent),
+    MouseMove(MouseEvent),
+    MouseEnter(MouseEvent),
+    MouseLeave(MouseEvent),
+    KeyDown(KeyboardEvent),
+    KeyUp(KeyboardEvent)
+}
+
+impl Event {
+    /// Returns whether this event propagates past the node it happened on.
+    pub fn propagates(&self) -> bool {
+        match self {
+            Event::MouseEnter(_) | Event::MouseLeave(_) => false,
+
+            Event::Click(_) | Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) |
+            Event::KeyDown(_) | Event::KeyUp(_) => true
+        }
+    }
+
+    /// Returns whether a handler has stopped this event from propagating.
+    pub fn is_propagation_stopped(&self) -> bool {
+        match self {
+            Event::Click(event) | Event::MouseDown(event) | Event::MouseUp(event) |
+            Event::MouseMove(event) | Event::MouseEnter(event) | Event::MouseLeave(event) => {
+                event.is_propagation_stopped()
+            },
+
+            Event::KeyDown(event) | Event::KeyUp(event) => event.is_propagation_stopped()
+        }
+    }
+
+    /// Runs this event through the handlers along `path` (ordered from the root down to the
+    /// target): capture handlers first, top-down, and then regular handlers, bottom-up.
+    pub(crate) fn propagate(&self, path: &[Arc<EventHandlers>]) {
+        let path = if self.propagates() { path } else { &path[path.len().saturating_sub(1)..] };
+
+        let phases = path.iter().map(|handlers| &handlers.capture)
+            .chain(path.iter().rev().map(|handlers| &handlers.bubble));
+
+        for handlers in phases {
+            handlers.handle(self);
+
+            if self.is_propagation_stopped() {
+                return;
+            }
+        }
+    }
+}
+
+/// A handler for events of type `E`.
+pub type EventHandler<E> = Arc<Fn(&E) + Send + Sync>;
+
+/// A handler for `MouseEvent`s.
+pub type MouseEventHandler = EventHandler<MouseEvent>;
+
+/// A handler for `KeyboardEvent`s.
+pub type KeyboardEventHandler = EventHandler<KeyboardEvent>;
+
+/// The event handlers set on a node, for each phase of propagation.
+#[derive(Clone, Default)]
+pub struct EventHandlers {
+    /// Handlers that run on the way down to the target (e.g, `onClickCapture`).
+    pub capture: PhaseHandlers,
+
+    /// Handlers that run on the way back up from the target (e.g, `onClick`).
+    pub bubble: PhaseHandlers
+}
+
+/// The event handlers set on a node, for a single phase of propagation.
+#[derive(Clone, Default)]
+pub struct PhaseHandlers {
+    pub on_click: Option<MouseEventHandler>,
+    pub on_mouse_down: Option<MouseEventHandler>,
+    pub on_mouse_up: Option<MouseEventHandler>,
+    pub on_mouse_move: Option<MouseEventHandler>,
+    pub on_mouse_enter: Option<MouseEventHandler>,
+    pub on_mouse_leave: Option<MouseEventHandler>,
+    pub on_key_down: Option<KeyboardEventHandler>,
+    pub on_key_up: Option<KeyboardEventHandler>
+}
+
+impl PhaseHandlers {
+    /// Calls the handler for `event`, if there is one.
+    pub fn handle(&self, event: &Event) {
+        match event {
+            Event::Click(event) => call(&self.on_click, event),
+            Event::MouseDown(event) => call(&self.on_mouse_down, event),
+            Event::MouseUp(event) => call(&self.on_mouse_up, event),
+            Event::MouseMove(event) => call(&self.on_mouse_move, event),
+            Event::MouseEnter(event) => call(&self.on_mouse_enter, event),
+            Event::MouseLeave(event) => call(&self.on_mouse_leave, event),
+            Event::KeyDown(event) => call(&self.on_key_down, event),
+            Event::KeyUp(event) => call(&self.on_key_up, event)
+        }
+    }
+}
+
+/// Calls `handler` with `event`, if it's set.
+fn call<E>(handler: &Option<EventHandler<E>>, event: &E) {
+    if let Some(handler) = handler {
+        handler(event);
+    }
+}
+
+/// Returns whether two handles point at the same native node.
+#[cfg(feature = "cocoa")]
+pub(crate) fn is_same_native_node(a: &PlatformSpecificNodeType, b: &PlatformSpecificNodeType) -> bool {
+    std::ptr::eq(&**a, &**b)
+}
+
+/// Returns whether two handles point at the same native node.
+#[cfg(all(feature = "headless", not(feature = "cocoa")))]
+pub(crate) fn is_same_native_node(a: &PlatformSpecificNodeType, b: &PlatformSpecificNodeType) -> bool {
+    a == b
+}
+
+/// Returns whether two handles point at the same native node. Without a backend, there aren't
+/// any.
+#[cfg(not(any(feature = "cocoa", feature = "headless")))]
+pub(crate) fn is_same_native_node(_a: &PlatformSpecificNodeType, _b: &PlatformSpecificNodeType) -> bool {
+    false
+}
+
+/// Returns something that identifies the native node behind `node`, for as long as it's alive.
+#[cfg(feature = "cocoa")]
+pub(crate) fn native_node_id(node: &PlatformSpecificNodeType) -> usize {
+    &**node as *const _ as usize
+}
+
+/// Returns something that identifies the native node behind `node`, for as long as it's alive.
+#[cfg(all(feature = "headless", not(feature = "cocoa")))]
+pub(crate) fn native_node_id(node: &PlatformSpecificNodeType) -> usize {
+    node.id()
+}
+
+/// Returns something that identifies the native node behind `node`. Without a backend, there
+/// aren't any.
+#[cfg(not(any(feature = "cocoa", feature = "headless")))]
+pub(crate) fn native_node_id(_node: &PlatformSpecificNodeType) -> usize {
+    0
+}
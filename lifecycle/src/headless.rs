@@ -0,0 +1,240 @@
+//! Implements an in-memory native node, used when Alchemy is compiled with the `headless`
+//! feature. There's no windowserver behind these; they simply record what a platform view
+//! layer would have been told to do (frames, appearance, text, accessibility, child order). This makes it
+//! possible to run the entire render pipeline on machines without a display (e.g, CI), and
+//! assert on the result.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alchemy_styles::styles::{Appearance, Layout, Point, Size};
+
+use crate::RENDER_ENGINE;
+use crate::accessibility::AccessibilityProperties;
+use crate::events::Event;
+
+/// Used for handing out unique IDs to nodes, which makes debugging output a bit more readable.
+static NEXT_NODE_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// The actual data for a node. This is guarded behind a `Mutex` in `NativeNode`, and never
+/// handed out directly.
+struct NodeData {
+    id: usize,
+    kind: &'static str,
+    location: Point<f32>,
+    size: Size<f32>,
+    appearance: Appearance,
+    text: Option<String>,
+    styles_applied: usize,
+    accessibility: Option<AccessibilityProperties>,
+    parent: Option<Weak<Mutex<NodeData>>>,
+    children: Vec<NativeNode>
+}
+
+/// A reference-counted handle to an in-memory node. Cloning this is cheap, and clones point
+/// at the same underlying node - much like retaining an `NSView` on macOS.
+#[derive(Clone)]
+pub struct NativeNode(Arc<Mutex<NodeData>>);
+
+impl NativeNode {
+    /// Creates a new, detached node. `kind` is a loose descriptor of what this node stands in
+    /// for (e.g, "View", "Text", "Window").
+    pub fn new(kind: &'static str) -> NativeNode {
+        NativeNode(Arc::new(Mutex::new(NodeData {
+            id: NEXT_NODE_ID.fetch_add(1, Ordering::SeqCst),
+            kind,
+            location: Point { x: 0., y: 0. },
+            size: Size { width: 0., height: 0. },
+            appearance: Appearance::default(),
+            text: None,
+            styles_applied: 0,
+            accessibility: None,
+            parent: None,
+            children: vec![]
+        })))
+    }
+
+    /// Returns the unique ID for this node.
+    pub fn id(&self) -> usize {
+        self.0.lock().unwrap().id
+    }
+
+    /// Returns what kind of node this is standing in for.
+    pub fn kind(&self) -> &'static str {
+        self.0.lock().unwrap().kind
+    }
+
+    /// Returns the location of this node, relative to its parent.
+    pub fn location(&self) -> Point<f32> {
+        self.0.lock().unwrap().location
+    }
+
+    /// Returns the size of this node.
+    pub fn size(&self) -> Size<f32> {
+        self.0.lock().unwrap().size
+    }
+
+    /// Sets the frame of this node from a computed `Layout`.
+    pub fn set_frame(&self, layout: &Layout) {
+        let mut node = self.0.lock().unwrap();
+        node.location = layout.location;
+        node.size = layout.size;
+        node.styles_applied += 1;
+    }
+
+    /// Sets the frame of this node from raw values. Mostly useful for `Window`s, which don't
+    /// receive a `Layout`.
+    pub fn set_frame_values(&self, x: f32, y: f32, width: f32, height: f32) {
+        let mut node = self.0.lock().unwrap();
+        node.location = Point { x, y };
+        node.size = Size { width, height };
+    }
+
+    /// Returns a copy of the last `Appearance` applied to this node.
+    pub fn appearance(&self) -> Appearance {
+        self.0.lock().unwrap().appearance
+    }
+
+    /// Sets the `Appearance` for this node.
+    pub fn set_appearance(&self, appearance: &Appearance) {
+        let mut node = self.0.lock().unwrap();
+        node.appearance = *appearance;
+        node.styles_applied += 1;
+    }
+
+    /// Returns how many times a frame or `Appearance` has been set on this node (through
+    /// `set_frame()` or `set_appearance()`), for checking that a render only touched what it
+    /// needed to.
+    pub fn styles_applied(&self) -> usize {
+        self.0.lock().unwrap().styles_applied
+    }
+
+    /// Returns the text currently displayed by this node, if any.
+    pub fn text(&self) -> Option<String> {
+        self.0.lock().unwrap().text.clone()
+    }
+
+    /// Sets the text displayed by this node.
+    pub fn set_text(&self, text: &str) {
+        self.0.lock().unwrap().text = Some(text.to_string());
+    }
+
+    /// Returns a copy of the last accessibility properties set on this node, if any.
+    pub fn accessibility(&self) -> Option<AccessibilityProperties> {
+        self.0.lock().unwrap().accessibility.clone()
+    }
+
+    /// Sets the accessibility properties for this node.
+    pub fn set_accessibility(&self, properties: &AccessibilityProperties) {
+        self.0.lock().unwrap().accessibility = Some(properties.clone());
+    }
+
+    /// Returns the parent of this node, if it's attached to one.
+    pub fn parent(&self) -> Option<NativeNode> {
+        let node = self.0.lock().unwrap();
+        node.parent.as_ref().and_then(Weak::upgrade).map(NativeNode)
+    }
+
+    /// Returns handles to the children of this node, in order.
+    pub fn children(&self) -> Vec<NativeNode> {
+        self.0.lock().unwrap().children.clone()
+    }
+
+    /// Appends a child node. Like `addSubview:` on macOS, if the child is already attached
+    /// somewhere, it's detached from there first.
+    pub fn append_child(&self, child: NativeNode) {
+        child.detach();
+        child.0.lock().unwrap().parent = Some(Arc::downgrade(&self.0));
+        self.0.lock().unwrap().children.push(child);
+    }
+
+    /// Inserts a child node at `index`, or appends it if `index` is past the end. Like
+    /// `addSubview:positioned:relativeTo:` on macOS, if the child is already attached somewhere
+    /// (including here), it's detached from there first.
+    pub fn insert_child(&self, child: NativeNode, index: usize) {
+        child.detach();
+        child.0.lock().unwrap().parent = Some(Arc::downgrade(&self.0));
+
+        let mut node = self.0.lock().unwrap();
+        let index = index.min(node.children.len());
+        node.children.insert(index, child);
+    }
+
+    /// Removes `child`, if it's a child of this node.
+    pub fn remove_child(&self, child: &NativeNode) {
+        if child.parent().as_ref() == Some(self) {
+            child.detach();
+        }
+    }
+
+    /// Swaps `old` out for `new`, keeping the position `old` had. Like `replaceSubview:with:` on
+    /// macOS, if `old` isn't a child of this node, nothing happens.
+    pub fn replace_child(&self, old: &NativeNode, new: NativeNode) {
+        if !self.children().contains(old) {
+            return;
+        }
+
+        new.detach();
+        old.0.lock().unwrap().parent = None;
+        new.0.lock().unwrap().parent = Some(Arc::downgrade(&self.0));
+
+        let mut node = self.0.lock().unwrap();
+        if let Some(index) = node.children.iter().position(|child| child == old) {
+            node.children[index] = new;
+        }
+    }
+
+    /// Detaches this node from its parent, if it has one.
+    pub fn detach(&self) {
+        let parent = self.0.lock().unwrap().parent.take();
+
+        if let Some(parent) = parent.as_ref().and_then(Weak::upgrade) {
+            parent.lock().unwrap().children.retain(|child| !Arc::ptr_eq(&child.0, &self.0));
+        }
+    }
+
+    /// Reports `event` against this node, as a windowserver would for input that landed on it.
+    /// Mouse locations should be relative to this node.
+    pub fn dispatch_event(&self, event: Event) -> Result<(), Box<std::error::Error>> {
+        RENDER_ENGINE.dispatch_event(self, event)
+    }
+
+    /// Walks this node and all of its descendants (depth-first), returning the first node
+    /// that satisfies `predicate`.
+    pub fn find<F: Fn(&NativeNode) -> bool>(&self, predicate: F) -> Option<NativeNode> {
+        fn walk<F: Fn(&NativeNode) -> bool>(node: &NativeNode, predicate: &F) -> Option<NativeNode> {
+            if predicate(node) {
+                return Some(node.clone());
+            }
+
+            node.children().iter().filter_map(|child| walk(child, predicate)).next()
+        }
+
+        walk(self, &predicate)
+    }
+}
+
+impl PartialEq for NativeNode {
+    /// Two handles are equal if they point at the same underlying node.
+    fn eq(&self, other: &NativeNode) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for NativeNode {
+    /// Prints the node and its children, which is handy when an assertion fails.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let node = self.0.lock().unwrap();
+        let mut debug = f.debug_struct(node.kind);
+        debug.field("id", &node.id);
+        debug.field("location", &(node.location.x, node.location.y));
+        debug.field("size", &(node.size.width, node.size.height));
+
+        if let Some(text) = &node.text {
+            debug.field("text", text);
+        }
+
+        debug.field("children", &node.children).finish()
+    }
+}
@@ -0,0 +1,120 @@
+//! Implements `InspectorNode`, a snapshot of a mounted `Component` tree that's meant for
+//! debugging. When a layout looks wrong, grab one from `RenderEngine::inspect()` and either dump
+//! it as JSON for tooling, or print it (via `Display`) for pasting into a bug report:
+//!
+//! ```rust,ignore
+//! let tree = window.render_engine().inspect(window.render_key())?;
+//! println!("{}", tree);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use alchemy_styles::styles::{Appearance, Layout, Style};
+
+/// A single `Component` in an inspected tree, along with everything that was resolved for it.
+#[derive(Clone, Debug, Serialize)]
+pub struct InspectorNode {
+    /// The tag this `Component` was rendered with (e.g, "View").
+    pub tag: &'static str,
+
+    /// The style keys this `Component` was rendered with.
+    pub style_keys: Vec<String>,
+
+    /// The `Style` resolved from `style_keys`. Only natively backed `Component`s have one.
+    pub style: Option<Style>,
+
+    /// The `Appearance` resolved from `style_keys`.
+    pub appearance: Appearance,
+
+    /// The last computed `Layout`. Only natively backed `Component`s have one.
+    pub layout: Option<Layout>,
+
+    /// Any text passed to this `Component` as children (e.g, for a `Text`).
+    pub text: Option<String>,
+
+    /// Whether this `Component` is backed by a native node.
+    pub is_native_backed: bool,
+
+    /// The children of this `Component`, in order.
+    pub children: Vec<InspectorNode>
+}
+
+impl InspectorNode {
+    /// Returns this tree as (pretty-printed) JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes this node, and its children, at the given depth.
+    fn write_indented(&self, f: &mut Formatter, depth: usize) -> std::fmt::Result {
+        let indent = "    ".repeat(depth);
+        write!(f, "{}{}", indent, self.tag)?;
+
+        if !self.style_keys.is_empty() {
+            write!(f, " styles=[{}]", self.style_keys.join(" "))?;
+        }
+
+        if let Some(text) = &self.text {
+            write!(f, " {:?}", text)?;
+        }
+
+        if self.is_native_backed {
+            write!(f, " native")?;
+        }
+
+        if let Some(layout) = &self.layout {
+            write!(f, " ({}, {}) {}x{}", layout.location.x, layout.location.y, layout.size.width, layout.size.height)?;
+        }
+
+        writeln!(f)?;
+
+        if let Some(style) = &self.style {
+            write_changed_fields(f, &indent, "style", style, &Style::default())?;
+        }
+
+        write_changed_fields(f, &indent, "appearance", &self.appearance, &Appearance::default())?;
+
+        for child in &self.children {
+            child.write_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for InspectorNode {
+    /// Prints the tree, one `Component` per line, indented by depth. `Style` and `Appearance`
+    /// are listed under each `Component`, but only the fields that differ from their defaults -
+    /// otherwise the interesting parts get buried.
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Writes the top-level fields of `value` that differ from `default`, on one line.
+fn write_changed_fields<T: Serialize>(
+    f: &mut Formatter,
+    indent: &str,
+    label: &str,
+    value: &T,
+    default: &T
+) -> std::fmt::Result {
+    let (value, default) = match (serde_json::to_value(value), serde_json::to_value(default)) {
+        (Ok(Value::Object(value)), Ok(Value::Object(default))) => (value, default),
+        _ => { return Ok(()); }
+    };
+
+    let changed: Vec<String> = value.iter()
+        .filter(|(name, field)| default.get(name.as_str()) != Some(field))
+        .map(|(name, field)| format!("{}={}", name, field))
+        .collect();
+
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "{}  {}: {}", indent, label, changed.join(", "))
+}
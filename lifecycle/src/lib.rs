@@ -15,16 +15,32 @@ pub use std::sync::Arc;
 
 use alchemy_styles::lazy_static;
 
+pub mod accessibility;
+pub mod context;
 pub mod error;
+pub mod events;
+pub mod inspector;
+pub mod recording;
+pub mod refs;
+pub mod remote;
 pub mod rsx;
 pub mod traits;
 
+#[cfg(feature = "headless")]
+pub mod headless;
+
 mod reconciler;
-use reconciler::RenderEngine;
+pub use reconciler::RenderEngine;
 pub use reconciler::key::ComponentKey;
+pub use reconciler::patch::Patch;
 
 lazy_static! {
-    pub static ref RENDER_ENGINE: RenderEngine = RenderEngine::new();
+    /// A shared `RenderEngine`, kept as a convenience. `Window`s each own their own engine, and
+    /// this one doesn't own any of their trees: it's only a router. Anything that takes a
+    /// `ComponentKey` here (e.g, `force_update()`) is passed along to whichever engine owns it,
+    /// so this can be used from anywhere. Roots are only rendered here if they're registered
+    /// with it directly.
+    pub static ref RENDER_ENGINE: Arc<RenderEngine> = RenderEngine::new();
 }
 
 #[macro_export]
@@ -2,21 +2,30 @@
 //! run. These are mostly internal to the rendering engine itself, but could potentially 
 //! show up elsewhere.
 
+use crate::error::{Error, ErrorInfo};
 use crate::reconciler::key::ComponentKey;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum RenderEngineError {
-    InvalidKey,
     InvalidRootComponent,
-    InvalidComponentKey(ComponentKey)
+    InvalidComponentKey(ComponentKey),
+    InvalidNodeId(u64),
+    PortalTargetInAnotherEngine(ComponentKey),
+    ComponentPanicked(String),
+    MaximumUpdateDepthExceeded,
+    UnsupportedRecordingVersion(u32)
 }
 
 impl std::fmt::Display for RenderEngineError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             RenderEngineError::InvalidComponentKey(ref node) => write!(f, "Invalid component key {:?}", node),
+            RenderEngineError::InvalidNodeId(id) => write!(f, "Invalid remote node id {}", id),
             RenderEngineError::InvalidRootComponent => write!(f, "Invalid component type! Root nodes must be a natively backed node."),
-            RenderEngineError::InvalidKey => write!(f, "An invalid key was passed to the render engine.")
+            RenderEngineError::PortalTargetInAnotherEngine(ref node) => write!(f, "Portal target {:?} belongs to another RenderEngine; portals can only target roots in their own.", node),
+            RenderEngineError::ComponentPanicked(ref message) => write!(f, "A component panicked while rendering: {}", message),
+            RenderEngineError::MaximumUpdateDepthExceeded => write!(f, "Maximum update depth exceeded. A component is likely queueing an update every time it's committed."),
+            RenderEngineError::UnsupportedRecordingVersion(version) => write!(f, "Recordings in format version {} aren't supported by this release.", version)
         }
     }
 }
@@ -25,8 +34,33 @@ impl std::error::Error for RenderEngineError {
     fn description(&self) -> &str {
         match *self {
             RenderEngineError::InvalidComponentKey(_) => "The key is not part of the component storage instance",
+            RenderEngineError::InvalidNodeId(_) => "The remote node id doesn't refer to a component in this engine.",
             RenderEngineError::InvalidRootComponent => "The root component must be a natively backed Component instance.",
-            RenderEngineError::InvalidKey => "An invalid key was passed to the render engine."
+            RenderEngineError::PortalTargetInAnotherEngine(_) => "A Portal's target must be registered with the same RenderEngine as the Portal.",
+            RenderEngineError::ComponentPanicked(_) => "A component panicked while rendering.",
+            RenderEngineError::MaximumUpdateDepthExceeded => "Updates kept being queued while committing, past the limit for a single flush.",
+            RenderEngineError::UnsupportedRecordingVersion(_) => "The recording was written in a newer format than this release supports."
         }
     }
 }
+
+/// Used internally to unwind a render pass back up to the error boundary that caught an error.
+/// This should never make it out of the reconciler.
+#[derive(Debug)]
+pub(crate) struct CaughtError {
+    pub boundary: ComponentKey,
+    pub error: Error,
+    pub info: ErrorInfo
+}
+
+impl std::fmt::Display for CaughtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Error caught by boundary {:?}: {}", self.boundary, self.error)
+    }
+}
+
+impl std::error::Error for CaughtError {
+    fn description(&self) -> &str {
+        "An error was caught by an error boundary."
+    }
+}
@@ -0,0 +1,102 @@
+//! Internal helpers for keyboard focus: working out what can take it, which window it belongs
+//! to, and the order Tab moves through it in. The focus itself is tracked by the
+//! `RenderEngine`, per root.
+
+use std::error::Error;
+
+use alchemy_styles::styles::Display;
+use alchemy_styles::stretch::node::Stretch as LayoutStore;
+
+use crate::reconciler::displayed_children;
+use crate::reconciler::key::ComponentKey;
+use crate::reconciler::instance::Instance;
+use crate::reconciler::storage::ComponentStore;
+
+/// Returns the tab index for `instance`, if it can take focus at all. `Component`s that accept
+/// focus without a `tabIndex` behave as if it were `0`.
+pub(crate) fn tab_index(instance: &Instance) -> Option<i32> {
+    match instance.tab_index {
+        Some(tab_index) => Some(tab_index),
+        None if instance.component.accepts_focus() => Some(0),
+        None => None
+    }
+}
+
+/// Returns the root that `key` is displayed under. This is usually the root of its tree, but
+/// content rendered through a `Portal` is displayed under the portal's target.
+pub(crate) fn focus_root(key: ComponentKey, components: &ComponentStore) -> Result<ComponentKey, Box<Error>> {
+    let mut current = key;
+
+    while let Some(parent) = components.parent(current)? {
+        if let Some(target) = components.get(parent)?.portal_target {
+            return Ok(target);
+        }
+
+        current = parent;
+    }
+
+    Ok(current)
+}
+
+/// Returns the nearest `Component` at or above `key` that can take focus, if any.
+pub(crate) fn nearest_focusable(
+    key: ComponentKey,
+    components: &ComponentStore
+) -> Result<Option<ComponentKey>, Box<Error>> {
+    let mut current = Some(key);
+
+    while let Some(key) = current {
+        if tab_index(components.get(key)?).is_some() {
+            return Ok(Some(key));
+        }
+
+        current = components.parent(key)?;
+    }
+
+    Ok(None)
+}
+
+/// Returns everything under `root` that Tab moves through, in order: positive tab indexes first
+/// (lowest first), and then everything else, in tree order. Hidden (`Display::None`) subtrees
+/// are skipped.
+pub(crate) fn tab_order(
+    root: ComponentKey,
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<Vec<ComponentKey>, Box<Error>> {
+    let mut entries = vec![];
+    collect_tab_stops(root, components, layouts, &mut entries)?;
+
+    // This is a stable sort, so tree order holds within each tab index.
+    entries.sort_by_key(|&(tab_index, _)| if tab_index > 0 { (0, tab_index) } else { (1, 0) });
+    Ok(entries.into_iter().map(|(_, key)| key).collect())
+}
+
+/// Walks the tree at `key` in the order it's displayed, collecting anything that Tab should stop
+/// at.
+fn collect_tab_stops(
+    key: ComponentKey,
+    components: &ComponentStore,
+    layouts: &LayoutStore,
+    entries: &mut Vec<(i32, ComponentKey)>
+) -> Result<(), Box<Error>> {
+    let instance = components.get(key)?;
+
+    if let Some(layout) = instance.layout {
+        if layouts.style(layout)?.display == Display::None {
+            return Ok(());
+        }
+    }
+
+    if let Some(tab_index) = tab_index(instance) {
+        if tab_index >= 0 {
+            entries.push((tab_index, key));
+        }
+    }
+
+    for child in displayed_children(key, components)? {
+        collect_tab_stops(child, components, layouts, entries)?;
+    }
+
+    Ok(())
+}
@@ -14,14 +14,8 @@ pub struct GenericRootViewProps;
 /// depends on this crate).
 pub struct GenericRootView;
 
-impl GenericRootView {
-    fn get_default_props() -> GenericRootViewProps {
-        GenericRootViewProps {}
-    }
-}
-
 impl Props for GenericRootView {
-    fn set_props(&mut self, _: &mut Any) {}    
+    fn set_props(&mut self, _: &Any) {}    
 }
 
 impl Component for GenericRootView {
@@ -1,15 +1,63 @@
 //! Internal struct used for tracking component instances and their 
 //! associated metadata (layout, appearance, etc).
 
-use alchemy_styles::{Appearance, StylesList};
+use std::any::Any;
+use std::sync::Arc;
+
+use alchemy_styles::{Appearance, Layout, StylesList};
+use alchemy_styles::styles::{FontStyle, FontWeight};
 use alchemy_styles::stretch::node::{Node as LayoutNode};
 
+use crate::events::EventHandlers;
+use crate::reconciler::key::ComponentKey;
+use crate::refs::Ref;
+use crate::rsx::RSX;
 use crate::traits::Component;
 
 pub(crate) struct Instance {
     pub(crate) tag: &'static str,
+    pub(crate) key: Option<String>,
+    pub(crate) node_ref: Option<Ref>,
+    pub(crate) tab_index: Option<i32>,
+    pub(crate) event_handlers: Arc<EventHandlers>,
     pub(crate) style_keys: StylesList,
     pub(crate) component: Box<Component + 'static>,
+    pub(crate) props: Arc<Any + Send + Sync>,
+    pub(crate) children: Vec<RSX>,
+    pub(crate) pending_update: Option<PendingUpdate>,
+    pub(crate) portal_target: Option<ComponentKey>,
+    pub(crate) provided_context: Option<Arc<Any + Send + Sync>>,
+    pub(crate) consumed_context: Vec<Arc<Any + Send + Sync>>,
     pub(crate) appearance: Appearance,
-    pub(crate) layout: Option<LayoutNode>
+    pub(crate) layout: Option<LayoutNode>,
+    pub(crate) last_applied_styles: Option<(Layout, Appearance)>,
+    pub(crate) measured: Option<MeasuredContent>
+}
+
+/// An update that's been rendered, but not yet reported through `component_did_update()`; that
+/// happens once layout and styles have been applied.
+pub(crate) struct PendingUpdate {
+    pub(crate) prev_props: Arc<Any + Send + Sync>,
+    pub(crate) snapshot: Option<Box<Any + Send>>
+}
+
+/// What a `Component`'s measure function was last built for: its text, and the font it's set
+/// in. Its layout node is only handed a new one when these change.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MeasuredContent {
+    pub(crate) text: Option<String>,
+    pub(crate) font_size: f32,
+    pub(crate) font_style: FontStyle,
+    pub(crate) font_weight: FontWeight
+}
+
+impl MeasuredContent {
+    pub(crate) fn new(text: Option<String>, appearance: &Appearance) -> MeasuredContent {
+        MeasuredContent {
+            text,
+            font_size: appearance.font_size,
+            font_style: appearance.font_style,
+            font_weight: appearance.font_weight
+        }
+    }
 }
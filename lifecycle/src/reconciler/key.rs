@@ -14,6 +14,18 @@ pub(crate) struct Id {
     id: u32
 }
 
+impl Id {
+    /// Wraps a raw ID, e.g one that's been sent over the wire.
+    pub(crate) fn new(id: u32) -> Id {
+        Id { id }
+    }
+
+    /// Returns the raw value of this ID.
+    pub(crate) fn value(self) -> u32 {
+        self.id
+    }
+}
+
 pub(crate) struct Allocator {
     new_id: u32
 }
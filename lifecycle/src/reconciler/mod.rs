@@ -2,381 +2,2510 @@
 //! you find littered around the web, this is a bit more ECS-ish, and expects Components to retain 
 //! their `ComponentKey` passed in their constructor if they want to update. Doing this 
 //! enables us to avoid re-scanning or diffing an entire tree.
+//!
+//! Diffing doesn't touch the backing native tree directly; it records what needs doing as a list
+//! of `Patch`es, which are then committed in one go (see the `patch` module).
 
-use std::sync::Mutex;
+use std::any::Any;
+use std::sync::{Arc, Mutex, Weak};
 use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::collections::{HashMap, VecDeque};
 
-use alchemy_styles::THEME_ENGINE;
-use alchemy_styles::styles::{Appearance, Dimension, Number, Size, Style};
-use alchemy_styles::stretch::node::{Node as LayoutNode, Stretch as LayoutStore};
+use alchemy_styles::{lazy_static, THEME_ENGINE};
+use alchemy_styles::styles::{Appearance, Dimension, Display, Layout, Number, Overflow, Point, Size, Style};
+use alchemy_styles::stretch::node::Stretch as LayoutStore;
 
+use crate::accessibility::{AccessibilityNode, AccessibilityProperties, Frame};
+use crate::context::Context;
+use crate::error::ErrorInfo;
+use crate::events::{native_node_id, Event, EventHandlers, MouseEvent};
+use crate::inspector::InspectorNode;
+use crate::recording::{self, Action, Recorder, Recording};
+use crate::remote::{self, NodeId, Operation};
 use crate::rsx::{RSX, VirtualNode};
-use crate::traits::Component;
+use crate::traits::{Component, PlatformSpecificNodeType};
 
 pub mod key;
-use key::ComponentKey;
+use key::{ComponentKey, Id};
 
 pub mod storage;
 use storage::ComponentStore;
 
 pub mod error;
-use error::RenderEngineError;
+use error::{CaughtError, RenderEngineError};
+
+mod focus;
+use focus::{focus_root, nearest_focusable, tab_order};
 
 mod instance;
-use instance::Instance;
+use instance::{Instance, MeasuredContent, PendingUpdate};
+
+pub mod patch;
+use patch::Patch;
 
 mod generic_root_view_stub;
 use generic_root_view_stub::{GenericRootView, GenericRootViewProps};
 
-struct GenericRootProps;
+/// Called with the `Operation`s for every commit (see `RenderEngine::on_commit()`).
+pub(crate) type CommitListener = Box<Fn(&[Operation]) + Send>;
+
+/// A state mutation for a `Component`.
+type StateUpdater = Box<FnOnce(&mut Component) + Send>;
+
+/// An update for a `Component`, queued up to be applied on the next flush.
+enum QueuedUpdate {
+    /// A state mutation, after which the `Component` re-renders as usual.
+    State(ComponentKey, StateUpdater),
+
+    /// A re-render that skips `should_component_update()`.
+    Force(ComponentKey)
+}
+
+/// Updates waiting to be applied, along with how many passes (see `Pass`) are underway. The two
+/// are kept under one lock, so that whoever queues an update knows whether a pass will see it.
+#[derive(Default)]
+struct UpdateQueue {
+    updates: Vec<QueuedUpdate>,
+    passes: usize
+}
+
+/// A render, commit or flush that's underway on a `RenderEngine`. Anything forced while one is
+/// open is left for it to pick up (see `RenderEngine::force_update()`), so it has to check the
+/// queue one last time as it ends - which `Pass::end_if_idle()` does, under the queue's lock.
+struct Pass<'a> {
+    engine: &'a RenderEngine,
+    is_open: bool
+}
+
+impl<'a> Pass<'a> {
+    /// Opens a pass on `engine`.
+    fn begin(engine: &'a RenderEngine) -> Pass<'a> {
+        engine.queued_state_updates.lock().unwrap().passes += 1;
+        Pass { engine, is_open: true }
+    }
+
+    /// Ends this pass if nothing's queued, returning whether it did.
+    fn end_if_idle(&mut self) -> bool {
+        let mut queue = self.engine.queued_state_updates.lock().unwrap();
+
+        if self.is_open && queue.updates.is_empty() {
+            queue.passes -= 1;
+            self.is_open = false;
+        }
+
+        !self.is_open
+    }
+}
+
+impl<'a> Drop for Pass<'a> {
+    /// Ends this pass, if it's being cut short (e.g, by an error).
+    fn drop(&mut self) {
+        if self.is_open {
+            self.engine.queued_state_updates.lock().unwrap().passes -= 1;
+        }
+    }
+}
+
+/// How many commits a single flush makes before giving up, on the assumption that something's
+/// queueing an update every time it's committed (e.g, from `component_did_update()`).
+const MAXIMUM_UPDATE_COMMITS: usize = 50;
+
+lazy_static! {
+    /// Every live `RenderEngine`, by the `Id` its `ComponentKey`s carry, so that a key can be
+    /// routed to the engine that owns it.
+    static ref RENDER_ENGINES: Mutex<HashMap<Id, Weak<RenderEngine>>> = Mutex::new(HashMap::new());
+}
+
+/// Owns a set of `Component` trees (typically, one per `Window`) along with their layout, and
+/// handles rendering and updating them. Each engine locks independently, so one rendering
+/// doesn't block any other.
+pub struct RenderEngine {
+    id: Id,
+    queued_state_updates: Mutex<UpdateQueue>,
+    mouse_down_target: Mutex<Option<ComponentKey>>,
+    focused: Mutex<HashMap<ComponentKey, ComponentKey>>,
+    commit_listener: Mutex<Option<CommitListener>>,
+    recorder: Mutex<Option<Recorder>>,
+    native_nodes: Mutex<HashMap<usize, ComponentKey>>,
+    components: Mutex<ComponentStore>,
+    layouts: Mutex<LayoutStore>
+}
+
+impl RenderEngine {
+    /// Creates a new, empty `RenderEngine`. Roots registered with it (and the `Component`s
+    /// beneath them) are isolated from every other engine.
+    pub fn new() -> Arc<RenderEngine> {
+        let component_store = ComponentStore::new();
+        let engine = Arc::new(RenderEngine {
+            id: component_store.id(),
+            queued_state_updates: Mutex::new(UpdateQueue::default()),
+            mouse_down_target: Mutex::new(None),
+            focused: Mutex::new(HashMap::new()),
+            commit_listener: Mutex::new(None),
+            recorder: Mutex::new(None),
+            native_nodes: Mutex::new(HashMap::new()),
+            components: Mutex::new(component_store),
+            layouts: Mutex::new(LayoutStore::new())
+        });
+
+        RENDER_ENGINES.lock().unwrap().insert(engine.id, Arc::downgrade(&engine));
+        engine
+    }
+
+    /// Returns the `RenderEngine` that owns `key`, if it's still around.
+    pub fn for_key(key: ComponentKey) -> Option<Arc<RenderEngine>> {
+        RENDER_ENGINES.lock().unwrap().get(&key.instance).and_then(Weak::upgrade)
+    }
+
+    /// Returns this engine's ID, which every `ComponentKey` it hands out carries.
+    pub(crate) fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns the engine that owns `key`, if it's one other than this. Anything that takes a
+    /// `ComponentKey` is routed through this, so that calls made through the global
+    /// `RENDER_ENGINE` reach the right tree.
+    fn other_owner(&self, key: ComponentKey) -> Option<Arc<RenderEngine>> {
+        if key.instance == self.id {
+            return None;
+        }
+
+        RenderEngine::for_key(key)
+    }
+
+    /// Queues a state update for the `Component` at `key`. The `updater` receives the
+    /// `Component` (downcast to `C`) and can mutate it as it sees fit; it's not applied right
+    /// away, but batched up with any other updates until the next call to
+    /// `flush_queued_updates()` (or the next root render) on the engine that owns `key` - see
+    /// `RenderEngine::for_key()`. If the `Component` has been unmounted by then, or isn't a `C`,
+    /// the update is dropped.
+    ///
+    /// This is safe to call from any thread, and from within lifecycle methods.
+    pub fn queue_update_for<C, F>(&self, key: ComponentKey, updater: F)
+    where
+        C: Component + 'static,
+        F: FnOnce(&mut C) + Send + 'static
+    {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.queue_update_for(key, updater);
+        }
+
+        if let Ok(target) = recording::relative_node_id(self.id, key) {
+            self.record(Action::StateUpdate { target, forced: false });
+        }
+
+        let mut queue = self.queued_state_updates.lock().unwrap();
+        queue.updates.push(QueuedUpdate::State(key, Box::new(move |component: &mut Component| {
+            if let Some(component) = component.as_any_mut().downcast_mut::<C>() {
+                updater(component);
+            }
+        })));
+    }
+
+    /// Re-renders the `Component` at `key`, skipping `should_component_update()`, and then
+    /// recomputes layout for the root it belongs to. If a render, commit or flush is underway
+    /// (e.g, this was called from a lifecycle method or an event handler, or another thread is
+    /// rendering), the re-render is queued and picked up by that pass, before it finishes.
+    /// Otherwise, this waits for the tree and flushes.
+    ///
+    /// Don't call this from within `with_component()`; the tree is locked, with no pass underway.
+    pub fn force_update(&self, key: ComponentKey) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.force_update(key);
+        }
+
+        if let Ok(target) = recording::relative_node_id(self.id, key) {
+            self.record(Action::StateUpdate { target, forced: true });
+        }
+
+        {
+            let mut queue = self.queued_state_updates.lock().unwrap();
+            queue.updates.push(QueuedUpdate::Force(key));
+
+            if queue.passes > 0 {
+                return Ok(());
+            }
+        }
+
+        self.flush_queued_updates()
+    }
+
+    /// Applies any state updates queued on this engine, re-renders the affected `Component`
+    /// subtrees, and then recomputes layout and re-applies styles for the roots they belong to. Updates queued
+    /// while this runs (e.g, from a lifecycle method) are picked up in the same flush, with
+    /// anything queued while committing getting a commit of its own.
+    ///
+    /// Don't call this from within a lifecycle method or an updater; the tree is locked
+    /// while those run.
+    pub fn flush_queued_updates(&self) -> Result<(), Box<Error>> {
+        self.finish_pass(Pass::begin(self))
+    }
+
+    /// Locks the tree and flushes (see `flush()`), ending `pass`.
+    fn finish_pass(&self, mut pass: Pass) -> Result<(), Box<Error>> {
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        self.flush(&mut pass, &mut component_store, &mut layout_store)
+    }
+
+    /// Applies queued updates, then lays out and styles any roots that were affected, and
+    /// commits the result. Committing runs lifecycle methods, which can queue more updates; this
+    /// keeps going until the queue stays empty, so that nothing is left behind once the tree is
+    /// unlocked. Every pass ends with this.
+    fn flush(
+        &self,
+        pass: &mut Pass,
+        component_store: &mut ComponentStore,
+        layout_store: &mut LayoutStore
+    ) -> Result<(), Box<Error>> {
+        let mut commits = 0;
+
+        while !pass.end_if_idle() {
+            // A component that queues an update every time it commits would never stop.
+            if commits == MAXIMUM_UPDATE_COMMITS {
+                self.queued_state_updates.lock().unwrap().updates.clear();
+                return Err(Box::new(RenderEngineError::MaximumUpdateDepthExceeded));
+            }
+
+            let mut patches = vec![];
+            let rendered = self.render_queued_updates(component_store, layout_store, &mut patches);
+            self.commit_patches(patches, component_store);
+            rendered?;
+            commits += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the render phase for whatever's queued, and lays out the roots it affected.
+    fn render_queued_updates(
+        &self,
+        component_store: &mut ComponentStore,
+        layout_store: &mut LayoutStore,
+        patches: &mut Vec<Patch>
+    ) -> Result<(), Box<Error>> {
+        for root in self.apply_queued_updates(component_store, layout_store, patches)? {
+            let layout = component_store.get(root)?.layout.ok_or(RenderEngineError::InvalidRootComponent)?;
+            let size = layout_store.layout(layout)?.size;
+            compute_layout(root, Size {
+                width: Number::Defined(size.width),
+                height: Number::Defined(size.height)
+            }, component_store, layout_store, patches)?;
+        }
+
+        Ok(())
+    }
+
+    /// Drains the queue (until it stays empty), applying updates and re-rendering the affected
+    /// subtrees. Returns the root keys that need a layout pass as a result.
+    fn apply_queued_updates(
+        &self,
+        component_store: &mut ComponentStore,
+        layout_store: &mut LayoutStore,
+        patches: &mut Vec<Patch>
+    ) -> Result<Vec<ComponentKey>, Box<Error>> {
+        let mut roots = vec![];
+
+        loop {
+            let updates: Vec<QueuedUpdate> = self.queued_state_updates.lock().unwrap().updates.drain(..).collect();
+            if updates.is_empty() {
+                return Ok(roots);
+            }
+
+            let mut dirty = vec![];
+            let mut forced = vec![];
+            for update in updates {
+                let key = match update {
+                    QueuedUpdate::State(key, updater) => match component_store.get_mut(key) {
+                        Ok(instance) => {
+                            updater(&mut *instance.component);
+                            key
+                        },
+
+                        Err(_) => { continue; }
+                    },
+
+                    QueuedUpdate::Force(key) => {
+                        if !forced.contains(&key) {
+                            forced.push(key);
+                        }
+
+                        key
+                    }
+                };
+
+                if !dirty.contains(&key) {
+                    dirty.push(key);
+                }
+            }
+
+            // If an ancestor is being re-rendered, it'll handle re-rendering its descendants...
+            // unless the descendant is being forced, as the ancestor would still ask it
+            // `should_component_update()`.
+            let mut subtrees = vec![];
+            for key in &dirty {
+                let mut ancestor = if forced.contains(key) { None } else { component_store.parent(*key)? };
+                let mut covered = false;
+
+                while let Some(parent) = ancestor {
+                    if dirty.contains(&parent) {
+                        covered = true;
+                        break;
+                    }
+
+                    ancestor = component_store.parent(parent)?;
+                }
+
+                if !covered {
+                    subtrees.push(*key);
+                }
+            }
+
+            for key in subtrees {
+                // An earlier re-render in this pass may have unmounted this one.
+                if component_store.get(key).is_err() {
+                    continue;
+                }
+
+                let props = component_store.get(key)?.props.clone();
+                update_component_tree(key, forced.contains(&key), props, component_store, layout_store, patches)?;
+
+                let mut root = key;
+                while let Some(parent) = component_store.parent(root)? {
+                    root = parent;
+                }
+
+                if !roots.contains(&root) {
+                    roots.push(root);
+                }
+            }
+        }
+    }
+
+    /// Calls `handler` with the `Component` at `key`, returning the result.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn with_component<R, F: FnOnce(&Component) -> R>(&self, key: ComponentKey, handler: F) -> Result<R, Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.with_component(key, handler);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        Ok(handler(&*component_store.get(key)?.component))
+    }
+
+    /// Calls `handler` with the natively backed `Component` at `key`, and how many native
+    /// children this engine has placed under it (its own, and anything portaled in). Those always
+    /// come first; a `Mirror` places what it mirrors after them.
+    pub(crate) fn with_native_parent<R, F: FnOnce(&Component, usize) -> R>(
+        &self,
+        key: ComponentKey,
+        handler: F
+    ) -> Result<R, Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.with_native_parent(key, handler);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        let count = native_children(key, &component_store)?.len();
+        Ok(handler(&*component_store.get(key)?.component, count))
+    }
+
+    /// Returns the native node backing the `Component` at `key`, if it has one.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn native_node_for(&self, key: ComponentKey) -> Result<Option<PlatformSpecificNodeType>, Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.native_node_for(key);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        Ok(component_store.get(key)?.component.borrow_native_backing_node())
+    }
+
+    /// Returns the last computed `Layout` for the `Component` at `key`, if it's natively backed.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn layout_for(&self, key: ComponentKey) -> Result<Option<Layout>, Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.layout_for(key);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        let layout_store = self.layouts.lock().unwrap();
+
+        match component_store.get(key)?.layout {
+            Some(layout) => Ok(Some(*layout_store.layout(layout)?)),
+            None => Ok(None)
+        }
+    }
+
+    /// Routes `event`, reported by a backend against the native `node`, to the `Component`
+    /// backed by that node - see `dispatch_event_to()`. Events on nodes that aren't mounted are
+    /// dropped.
+    ///
+    /// This checks every engine for `node`, so backends can call it through the global
+    /// `RENDER_ENGINE`. Each engine keeps track of which `Component` its committed native nodes
+    /// belong to, so this doesn't need the tree locked to find out - but don't call it from
+    /// within a lifecycle method regardless, as propagating the event does.
+    pub fn dispatch_event(&self, node: &PlatformSpecificNodeType, event: Event) -> Result<(), Box<Error>> {
+        let engines: Vec<Arc<RenderEngine>> = RENDER_ENGINES.lock().unwrap().values().filter_map(Weak::upgrade).collect();
+        let id = native_node_id(node);
+
+        for engine in engines {
+            let key = engine.native_nodes.lock().unwrap().get(&id).cloned();
+
+            if let Some(key) = key {
+                return engine.dispatch_event_to(key, event);
+            }
+        }
+
+        // It may belong to a tree that's mirrored from elsewhere, instead.
+        remote::dispatch_to_mirrors(node, event)
+    }
+
+    /// Propagates `event` to the `Component` at `key` (see the `events` module for how), and then
+    /// flushes any updates that handlers queued. A mouse up on the same `Component` as the last
+    /// mouse down is followed by a `Click`. Keyboard events go to the focused `Component` in the
+    /// same window instead, if there is one.
+    ///
+    /// Handlers are called with the tree unlocked, so they're free to use `Ref`s and the like.
+    /// Don't call this from within a lifecycle method, though; the tree is locked while those
+    /// run.
+    pub fn dispatch_event_to(&self, key: ComponentKey, event: Event) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.dispatch_event_to(key, event);
+        }
+
+        if let Ok(target) = recording::relative_node_id(self.id, key) {
+            self.record(Action::Input { target, event: event.clone() });
+        }
+
+        let pass = Pass::begin(self);
+        let key = match &event {
+            Event::KeyDown(_) | Event::KeyUp(_) => {
+                let component_store = self.components.lock().unwrap();
+                let root = focus_root(key, &component_store)?;
+                self.current_focus(root, &component_store)?.unwrap_or(key)
+            },
+
+            _ => key
+        };
+
+        let click = match &event {
+            Event::MouseDown(_) => {
+                *self.mouse_down_target.lock().unwrap() = Some(key);
+                None
+            },
+
+            Event::MouseUp(event) => match self.mouse_down_target.lock().unwrap().take() {
+                Some(target) if target == key => {
+                    Some(Event::Click(MouseEvent::new(event.location, event.button, event.modifiers)))
+                },
+
+                _ => None
+            },
+
+            _ => None
+        };
+
+        for event in Some(event).into_iter().chain(click) {
+            let path = {
+                let component_store = self.components.lock().unwrap();
+                event_handlers_along_path(key, &component_store)?
+            };
+
+            event.propagate(&path);
+            self.perform_default_action(key, &event)?;
+        }
+
+        self.finish_pass(pass)
+    }
+
+    /// Carries out what `event` does beyond its handlers, unless one of them stopped it: a mouse
+    /// down focuses whatever was clicked (or clears focus, if nothing there can take it), and
+    /// Tab/Shift-Tab move focus along the Tab order.
+    fn perform_default_action(&self, key: ComponentKey, event: &Event) -> Result<(), Box<Error>> {
+        if event.is_propagation_stopped() {
+            return Ok(());
+        }
+
+        let mut component_store = self.components.lock().unwrap();
+        let root = focus_root(key, &component_store)?;
+
+        match event {
+            Event::MouseDown(_) => {
+                let focusable = nearest_focusable(key, &component_store)?;
+                self.change_focus(root, focusable, &mut component_store)
+            },
+
+            // AppKit reports Shift-Tab as a back tab character.
+            Event::KeyDown(event) if event.key == "\t" || event.key == "\u{19}" => {
+                let forward = event.key == "\t" && !event.modifiers.shift;
+                self.advance_focus(root, forward, &mut component_store)
+            },
+
+            _ => Ok(())
+        }
+    }
+
+    /// Returns the `Component` with keyboard focus under `root` (e.g, a `Window`'s root), if
+    /// any.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn focused_component(&self, root: ComponentKey) -> Result<Option<ComponentKey>, Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.focused_component(root);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        self.current_focus(root, &component_store)
+    }
+
+    /// Gives keyboard focus to the `Component` at `key`, taking it from whatever had it in the
+    /// same window, and then flushes any updates that `component_did_blur()` or
+    /// `component_did_focus()` queued. Nothing happens if the `Component` can't take focus (see
+    /// `Component::accepts_focus()`).
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn focus(&self, key: ComponentKey) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.focus(key);
+        }
+
+        let pass = Pass::begin(self);
+        {
+            let mut component_store = self.components.lock().unwrap();
+            if focus::tab_index(component_store.get(key)?).is_none() {
+                return Ok(());
+            }
+
+            let root = focus_root(key, &component_store)?;
+            self.change_focus(root, Some(key), &mut component_store)?;
+        }
+
+        self.finish_pass(pass)
+    }
+
+    /// Clears keyboard focus under `root`, and then flushes any updates that
+    /// `component_did_blur()` queued.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn clear_focus(&self, root: ComponentKey) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.clear_focus(root);
+        }
+
+        let pass = Pass::begin(self);
+        {
+            let mut component_store = self.components.lock().unwrap();
+            self.change_focus(root, None, &mut component_store)?;
+        }
+
+        self.finish_pass(pass)
+    }
+
+    /// Moves keyboard focus under `root` to the next `Component` in the Tab order (wrapping
+    /// around at the end), as pressing Tab does.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn focus_next(&self, root: ComponentKey) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.focus_next(root);
+        }
+
+        let pass = Pass::begin(self);
+        {
+            let mut component_store = self.components.lock().unwrap();
+            self.advance_focus(root, true, &mut component_store)?;
+        }
+
+        self.finish_pass(pass)
+    }
+
+    /// Moves keyboard focus under `root` to the previous `Component` in the Tab order (wrapping
+    /// around at the start), as pressing Shift-Tab does.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn focus_previous(&self, root: ComponentKey) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.focus_previous(root);
+        }
+
+        let pass = Pass::begin(self);
+        {
+            let mut component_store = self.components.lock().unwrap();
+            self.advance_focus(root, false, &mut component_store)?;
+        }
+
+        self.finish_pass(pass)
+    }
+
+    /// Returns the focused `Component` under `root`, provided it's still mounted there. Focus
+    /// isn't cleared when the focused `Component` is unmounted (or moved through a `Portal`), so
+    /// this is where that's caught.
+    fn current_focus(&self, root: ComponentKey, components: &ComponentStore) -> Result<Option<ComponentKey>, Box<Error>> {
+        let focused = self.focused.lock().unwrap().get(&root).cloned();
+
+        match focused {
+            Some(key) if components.get(key).is_ok() && focus_root(key, components)? == root => Ok(Some(key)),
+            _ => Ok(None)
+        }
+    }
+
+    /// Moves focus under `root` to `key` (or clears it), calling `component_did_blur()` and
+    /// `component_did_focus()` if it changed.
+    fn change_focus(
+        &self,
+        root: ComponentKey,
+        key: Option<ComponentKey>,
+        components: &mut ComponentStore
+    ) -> Result<(), Box<Error>> {
+        let previous = self.current_focus(root, components)?;
+        if previous == key {
+            return Ok(());
+        }
+
+        {
+            let mut focused = self.focused.lock().unwrap();
+            match key {
+                Some(key) => { focused.insert(root, key); },
+                None => { focused.remove(&root); }
+            }
+        }
+
+        if let Some(previous) = previous {
+            components.get_mut(previous)?.component.component_did_blur();
+        }
+
+        if let Some(key) = key {
+            components.get_mut(key)?.component.component_did_focus();
+        }
+
+        Ok(())
+    }
+
+    /// Moves focus under `root` one step along the Tab order. If nothing in the Tab order has
+    /// focus, this starts from the beginning (or the end, going backwards).
+    fn advance_focus(&self, root: ComponentKey, forward: bool, components: &mut ComponentStore) -> Result<(), Box<Error>> {
+        let order = {
+            let layout_store = self.layouts.lock().unwrap();
+            tab_order(root, components, &layout_store)?
+        };
+
+        if order.is_empty() {
+            return Ok(());
+        }
+
+        let current = self.current_focus(root, components)?.and_then(|key| order.iter().position(|k| *k == key));
+        let next = match current {
+            Some(index) if forward => (index + 1) % order.len(),
+            Some(index) => (index + order.len() - 1) % order.len(),
+            None if forward => 0,
+            None => order.len() - 1
+        };
+
+        self.change_focus(root, Some(order[next]), components)
+    }
+
+    /// Returns the path (from `root` down) to the deepest `Component` under `point`, which is
+    /// relative to `root`. Later siblings sit on top of earlier ones, nodes with `Display::None`
+    /// are skipped (along with their descendants), and nodes with `Overflow::Hidden` clip their
+    /// descendants. The path is empty if nothing's under `point`.
+    ///
+    /// The path follows the `Component` tree, since that's what events propagate along - so for
+    /// content rendered through a `Portal`, it runs through the `Portal` rather than its target.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn hit_test(&self, root: ComponentKey, point: Point<f32>) -> Result<Vec<ComponentKey>, Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.hit_test(root, point);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        let layout_store = self.layouts.lock().unwrap();
+
+        // `hit_test_component_tree()` works in the space of the parent node.
+        let point = match component_store.get(root)?.layout {
+            Some(layout) => {
+                let location = layout_store.layout(layout)?.location;
+                Point { x: point.x + location.x, y: point.y + location.y }
+            },
+
+            None => point
+        };
+
+        let mut current = match hit_test_component_tree(root, point, &component_store, &layout_store)? {
+            Some(target) => target,
+            None => { return Ok(vec![]); }
+        };
+
+        let mut path = vec![current];
+        while current != root {
+            current = match component_store.parent(current)? {
+                Some(parent) => parent,
+                None => { break; }
+            };
+
+            path.push(current);
+        }
+
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Walks the tree found at `key` and returns a snapshot of it, for debugging - see
+    /// `InspectorNode`.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn inspect(&self, key: ComponentKey) -> Result<InspectorNode, Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.inspect(key);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        let layout_store = self.layouts.lock().unwrap();
+        inspect_component_tree(key, &component_store, &layout_store)
+    }
+
+    /// Derives the accessibility tree for everything under `root` (e.g, a `Window`'s root) - see
+    /// `AccessibilityNode`. `root` is always included, even if it doesn't describe itself. Frames
+    /// are relative to `root`, and content is placed where it's displayed, so anything rendered
+    /// through a `Portal` shows up under the portal's target. Hidden (`Display::None`) subtrees
+    /// are left out.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn accessibility_tree(&self, root: ComponentKey) -> Result<AccessibilityNode, Box<Error>> {
+        if let Some(engine) = self.other_owner(root) {
+            return engine.accessibility_tree(root);
+        }
+
+        let component_store = self.components.lock().unwrap();
+        let layout_store = self.layouts.lock().unwrap();
+        let focused = self.current_focus(root, &component_store)?;
+
+        // Frames for the tree are computed in the space of `root`'s parent.
+        let instance = component_store.get(root)?;
+        let origin = match instance.layout {
+            Some(layout) => {
+                let location = layout_store.layout(layout)?.location;
+                Point { x: -location.x, y: -location.y }
+            },
+
+            None => Point { x: 0., y: 0. }
+        };
+
+        let properties = accessibility_properties(instance).unwrap_or_default();
+        accessibility_node(root, properties, origin, focused, &component_store, &layout_store)
+    }
+
+    /// `Window`'s (or anything "root" in nature) need to register with the 
+    /// reconciler for things like setState to work properly. When they do so,
+    /// they get a key back. When they want to instruct the global `RenderEngine` 
+    /// to re-render or update their tree, they pass that key and whatever the new tree 
+    /// should be.
+    pub fn register_root_component<C: Component + 'static>(&self, component: C) -> Result<ComponentKey, Box<Error>> {
+        // Conceivably, this doesn't NEED to be a thing... but for now it is. If you've stumbled
+        // upon here, wayward traveler, in need of a non-native-root-component, please open an
+        // issue to discuss. :)
+        if !component.has_native_backing_node() {
+            return Err(Box::new(RenderEngineError::InvalidRootComponent {}));
+        }
+
+        let mut component_store = self.components.lock().unwrap();
+        let mut layouts_store = self.layouts.lock().unwrap();
+        let component_key = component_store.new_key();
+        component_store.insert(component_key, Instance {
+            tag: "root",
+            key: None,
+            node_ref: None,
+            tab_index: None,
+            event_handlers: Arc::new(EventHandlers::default()),
+            style_keys: "root".into(),
+            component: Box::new(component),
+            props: Arc::new(GenericRootViewProps {}),
+            children: vec![],
+            pending_update: None,
+            portal_target: None,
+            provided_context: None,
+            consumed_context: vec![],
+            appearance: Appearance::default(),
+            layout: Some(layouts_store.new_node(Style::default(), vec![])?),
+            last_applied_styles: None,
+            measured: None
+        })?;
+
+        if let Some(node) = component_store.get(component_key)?.component.borrow_native_backing_node() {
+            self.native_nodes.lock().unwrap().insert(native_node_id(&node), component_key);
+        }
+
+        Ok(component_key)
+    }
+
+    /// Rendering the root node is a bit different than rendering or updating other nodes, as we 
+    /// never want to unmount it, and the results come from a non-`Component` entity (e.g, a
+    /// `Window`). Thus, for this one, we do some manual mucking with what we know is the
+    /// root view (a `Window` or such root component would call this with it's registered
+    /// `ComponentKey`), and then recurse based on the children.
+    ///
+    /// This runs both phases of rendering: the render phase (see `diff_root()`), and then the
+    /// commit phase (see `commit()`), followed by any updates that were queued while committing.
+    /// If the render phase fails, whatever it got through is still committed (see the `patch`
+    /// module), and then the error is returned.
+    pub fn diff_and_render_root(
+        &self,
+        key: ComponentKey,
+        dimensions: (f64, f64),
+        child: RSX
+    ) -> Result<(), Box<Error>> {
+        let mut pass = Pass::begin(self);
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        let mut patches = vec![];
+        let rendered = self.render_root(key, dimensions, child, &mut component_store, &mut layout_store, &mut patches);
+
+        self.commit_patches(patches, &mut component_store);
+        rendered?;
+        self.flush(&mut pass, &mut component_store, &mut layout_store)
+    }
+
+    /// Runs the render phase for the root at `key`: diffs `child` against what's there, and
+    /// computes layout, returning the changes that the backing tree needs (and the lifecycle
+    /// methods due once they're made) as a list of `Patch`es. Nothing native is touched.
+    ///
+    /// The `Component` tree moves on regardless, so these need to be passed to `commit()`, in
+    /// order, before anything else renders; otherwise the backing tree falls out of step with it.
+    /// If rendering fails (e.g, a `render()` errors with no boundary to catch it), there's
+    /// nothing to pass along: whatever it got through is committed before the error is returned.
+    /// Anything forced while this runs is left for `commit()`.
+    pub fn diff_root(&self, key: ComponentKey, dimensions: (f64, f64), child: RSX) -> Result<Vec<Patch>, Box<Error>> {
+        let _pass = Pass::begin(self);
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        let mut patches = vec![];
+
+        if let Err(e) = self.render_root(key, dimensions, child, &mut component_store, &mut layout_store, &mut patches) {
+            self.commit_patches(patches, &mut component_store);
+            return Err(e);
+        }
+
+        Ok(patches)
+    }
+
+    /// Runs the commit phase for `patches` (as returned by `diff_root()`): applies them to the
+    /// backing tree, in order, and then calls `component_did_mount()`, `component_did_update()`
+    /// and `component_did_catch()` as they record, children first (see `apply_patches()` for
+    /// exactly when). Any updates those queue are then rendered and committed as well.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn commit(&self, patches: Vec<Patch>) -> Result<(), Box<Error>> {
+        let mut pass = Pass::begin(self);
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        self.commit_patches(patches, &mut component_store);
+        self.flush(&mut pass, &mut component_store, &mut layout_store)
+    }
+
+    /// Sets a function to be called with every commit from here on out (replacing any set
+    /// before), translated into `Operation`s - see the `remote` module. It's called before the
+    /// commit is applied, with the tree locked.
+    pub fn on_commit<F: Fn(&[Operation]) + Send + 'static>(&self, listener: F) {
+        *self.commit_listener.lock().unwrap() = Some(Box::new(listener));
+    }
+
+    /// Swaps in `listener` (see `on_commit()`), returning whatever was set before.
+    pub(crate) fn replace_commit_listener(&self, listener: Option<CommitListener>) -> Option<CommitListener> {
+        std::mem::replace(&mut *self.commit_listener.lock().unwrap(), listener)
+    }
+
+    /// Starts recording everything this engine does (see the `recording` module), discarding
+    /// anything recorded so far.
+    pub fn start_recording(&self) {
+        *self.recorder.lock().unwrap() = Some(Recorder::new());
+    }
+
+    /// Stops recording, and returns what was recorded. Returns `None` if this engine wasn't
+    /// recording.
+    pub fn stop_recording(&self) -> Option<Recording> {
+        self.recorder.lock().unwrap().take().map(Recorder::finish)
+    }
+
+    /// Swaps in `recorder`, returning whatever was recording before.
+    pub(crate) fn replace_recorder(&self, recorder: Option<Recorder>) -> Option<Recorder> {
+        std::mem::replace(&mut *self.recorder.lock().unwrap(), recorder)
+    }
+
+    /// Takes the state updates recorded so far (see `Recorder::take_state_updates()`). Returns
+    /// nothing if this engine isn't recording.
+    pub(crate) fn take_recorded_state_updates(&self) -> Vec<(NodeId, bool)> {
+        match &mut *self.recorder.lock().unwrap() {
+            Some(recorder) => recorder.take_state_updates(),
+            None => vec![]
+        }
+    }
+
+    /// Logs `action`, if this engine is recording.
+    fn record(&self, action: Action) {
+        if let Some(recorder) = &mut *self.recorder.lock().unwrap() {
+            recorder.record(action);
+        }
+    }
+
+    /// Runs the commit phase for `patches`, letting the commit listener know first (if there is
+    /// one), and recording it (if this engine is recording).
+    fn commit_patches(&self, patches: Vec<Patch>, component_store: &mut ComponentStore) {
+        let listener = self.commit_listener.lock().unwrap();
+        let mut recorder = self.recorder.lock().unwrap();
+
+        if listener.is_some() || recorder.is_some() {
+            let operations = remote::operations_for(&patches, component_store);
+
+            if let Some(listener) = &*listener {
+                listener(&operations);
+            }
+
+            if let (Some(recorder), Ok(operations)) = (&mut *recorder, recording::relative_operations(self.id, &operations)) {
+                recorder.record(Action::Commit { operations });
+            }
+        }
+
+        drop(recorder);
+        drop(listener);
+        apply_patches(patches, component_store, &mut self.native_nodes.lock().unwrap());
+    }
+
+    /// Returns the `ComponentKey` in this engine for a `NodeId` from the `remote` module, or an
+    /// error if it doesn't belong to this engine.
+    pub(crate) fn key_for_node_id(&self, id: NodeId) -> Result<ComponentKey, Box<Error>> {
+        let key = remote::key_for_node_id(id)?;
+
+        if key.instance != self.id {
+            return Err(Box::new(RenderEngineError::InvalidNodeId(id)));
+        }
+
+        Ok(key)
+    }
+
+    /// Returns the `ComponentKey` in this engine for a `NodeId` that's relative to it, as
+    /// `Recording`s store them (see `recording::relative_node_id()`).
+    pub(crate) fn key_for_relative_node_id(&self, id: NodeId) -> Result<ComponentKey, Box<Error>> {
+        if id >> 32 != 0 {
+            return Err(Box::new(RenderEngineError::InvalidNodeId(id)));
+        }
+
+        self.key_for_node_id(NodeId::from(self.id.value()) << 32 | id)
+    }
+
+    /// Does the work for `diff_root()`.
+    fn render_root(
+        &self,
+        key: ComponentKey,
+        dimensions: (f64, f64),
+        child: RSX,
+        component_store: &mut ComponentStore,
+        layout_store: &mut LayoutStore,
+        patches: &mut Vec<Patch>
+    ) -> Result<(), Box<Error>> {
+        self.record(Action::Render { width: dimensions.0, height: dimensions.1 });
+
+        let new_root_node = RSX::node("root", "root".into(), |_| {
+            Box::new(GenericRootView {})
+        }, GenericRootViewProps {}, match child {
+            RSX::VirtualNode(node) => {
+                if node.tag == "Fragment" {
+                    node.children
+                } else {
+                    vec![RSX::VirtualNode(node)]
+                }
+            },
+
+            _ => vec![]
+        });
+
+        recursively_diff_tree(key, new_root_node, component_store, layout_store, patches)?;
+        self.apply_queued_updates(component_store, layout_store, patches)?;
+
+        layout_root(key, dimensions, component_store, layout_store, patches)
+    }
+
+    /// Lays out the root at `key` at a new size (e.g, when a `Window` is resized) without
+    /// re-rendering anything, and commits the result.
+    ///
+    /// Don't call this from within a lifecycle method; the tree is locked while those run.
+    pub fn resize_root(&self, key: ComponentKey, dimensions: (f64, f64)) -> Result<(), Box<Error>> {
+        if let Some(engine) = self.other_owner(key) {
+            return engine.resize_root(key, dimensions);
+        }
+
+        self.record(Action::Resize { width: dimensions.0, height: dimensions.1 });
+
+        let mut pass = Pass::begin(self);
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        let mut patches = vec![];
+        let laid_out = layout_root(key, dimensions, &mut component_store, &mut layout_store, &mut patches);
+
+        self.commit_patches(patches, &mut component_store);
+        laid_out?;
+        self.flush(&mut pass, &mut component_store, &mut layout_store)
+    }
+}
+
+impl Drop for RenderEngine {
+    /// Takes this engine out of the registry, so that keys it owned stop routing anywhere.
+    fn drop(&mut self) {
+        RENDER_ENGINES.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Sizes the root at `key` to `dimensions`, and then computes its layout.
+fn layout_root(
+    key: ComponentKey,
+    dimensions: (f64, f64),
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    {
+        let root_instance = component_store.get_mut(key)?;
+        let layout = root_instance.layout.ok_or(RenderEngineError::InvalidRootComponent)?;
+        let mut style = Style::default();
+        THEME_ENGINE.configure_styles_for_keys(&root_instance.style_keys, &mut style, &mut root_instance.appearance);
+        style.size = Size {
+            width: Dimension::Points(dimensions.0 as f32),
+            height: Dimension::Points(dimensions.1 as f32)
+        };
+
+        // Setting a style marks the node dirty, which would force a relayout of the root.
+        if *layout_store.style(layout)? != style {
+            layout_store.set_style(layout, style)?;
+        }
+    }
+
+    compute_layout(key, Size {
+        width: Number::Defined(dimensions.0 as f32),
+        height: Number::Defined(dimensions.1 as f32)
+    }, component_store, layout_store, patches)
+}
+
+/// Given two trees, will diff them to see if we need to replace or update. Depending on the
+/// result, we'll either recurse down a level, or tear down and build up a new tree. The final
+/// parameter on this method, `is_root_entity_view`, should only be passed for `Window` or other
+/// such instances, as it instructs us to skip the first level since these ones act different.
+///
+/// The `Ok` variant contains the `ComponentKey` that now occupies this slot in the tree; this is
+/// `key` for an update, and a new key if the node was replaced.
+fn recursively_diff_tree(
+    key: ComponentKey,
+    new_tree: RSX,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<ComponentKey, Box<Error>> {
+    // First we need to determine if this node is being replaced or updated. A replace happens if 
+    // two nodes are different types - in this case, we check their tag values. This is also a case
+    // where, for instance, if the RSX tag is `::None` or `::VirtualText`, we'll treat it as
+    // replacing with nothing.
+    let is_replace = match &new_tree {
+        RSX::VirtualNode(new_tree) => {
+            let old_tree = component_store.get(key)?;
+            old_tree.tag != new_tree.tag
+        },
+
+        // The algorithm will know below not to recurse if we're trying to diff text or empty
+        // values. We return false here to avoid entering the `is_replace` phase; `Component`
+        // instances (like <Text />) handle taking the child VirtualText instances and working with
+        // them to pass to a native widget.
+        _ => false
+    };
+
+    if is_replace {
+        if let RSX::VirtualNode(new_tree) = new_tree {
+            return replace_component_tree(key, new_tree, component_store, layout_store, patches);
+        }
+    }
+
+    // At this point, we know it's an update pass. Now we need to do a few things:
+    //
+    // - Diff our `props` and figure out what actions we can take or shortcut.
+    // - Let the `Component` instance determine what it should render.
+    // - Recurse into the child trees if necessary.
+    let new_tree = match new_tree {
+        RSX::VirtualNode(new_tree) => new_tree,
+        _ => { return Ok(key); }
+    };
+
+    let instance = component_store.get_mut(key)?;
+
+    // A different `Ref` (or none at all) may have been passed this time around.
+    if instance.node_ref != new_tree.node_ref {
+        if let Some(node_ref) = instance.node_ref.take() {
+            patches.push(Patch::DetachRef { key, node_ref });
+        }
+
+        if let Some(node_ref) = &new_tree.node_ref {
+            patches.push(Patch::AttachRef { key, node_ref: node_ref.clone() });
+        }
+    }
+
+    instance.node_ref = new_tree.node_ref.clone();
+
+    let old_accessibility = accessibility_properties(instance);
+    instance.tab_index = new_tree.tab_index;
+
+    // Handlers are typically closures created in `render()`, so they're swapped in every time,
+    // even if nothing else changed.
+    instance.event_handlers = new_tree.event_handlers.clone();
+
+    // Memoized components can skip the rest of this entirely if nothing's changed.
+    if instance.style_keys == new_tree.styles
+        && instance.component.props_are_equal(&*instance.props, &*new_tree.props)
+        && children_are_equal(&instance.children, &new_tree.children) {
+        return Ok(key);
+    }
+
+    // If the styles changed, they need to be re-resolved before the next layout pass.
+    if instance.style_keys != new_tree.styles {
+        instance.style_keys = new_tree.styles;
+
+        if let Some(layout) = instance.layout {
+            let mut style = Style::default();
+            instance.appearance = Appearance::default();
+            THEME_ENGINE.configure_styles_for_keys(&instance.style_keys, &mut style, &mut instance.appearance);
+
+            if *layout_store.style(layout)? != style {
+                layout_store.set_style(layout, style)?;
+            }
+        }
+    }
+
+    instance.component.set_props(&*new_tree.props);
+    let prev_props = std::mem::replace(&mut instance.props, new_tree.props);
+
+    // Whatever stops describing itself goes back to the defaults.
+    let accessibility = accessibility_properties(instance);
+    if accessibility != old_accessibility {
+        patches.push(Patch::SetAccessibility { key, properties: accessibility.unwrap_or_default() });
+    }
+
+    let old_text = text_content(instance);
+    instance.children = new_tree.children;
+
+    let text = text_content(instance);
+    if text != old_text {
+        patches.push(Patch::SetText { key, text: text.unwrap_or_default() });
+    }
+
+    // If a portal was pointed somewhere else, its children need to move over - so detach them
+    // from wherever they are now, and link them back in once they've been updated. If it's
+    // somewhere they can't go, that's reported like any other render error, and they stay put.
+    let (portal_target, old_portal_target) = (instance.component.portal_target(), instance.portal_target);
+    let portal_target = match check_portal_target(portal_target, component_store) {
+        Ok(portal_target) => portal_target,
+        Err(e) => {
+            handle_render_error(key, e, component_store)?;
+            old_portal_target
+        }
+    };
+
+    let is_retargeted = portal_target != old_portal_target;
+    if is_retargeted {
+        for child in component_store.children(key)? {
+            detach_native_nodes(child, component_store, layout_store, patches)?;
+        }
+
+        component_store.get_mut(key)?.portal_target = portal_target;
+        match portal_target {
+            Some(_) => component_store.add_portal(key),
+            None => component_store.remove_portal(key)
+        }
+    }
+
+    update_component_tree(key, false, prev_props, component_store, layout_store, patches)?;
+
+    if is_retargeted {
+        relink_native_children(key, component_store, layout_store, patches)?;
+    }
+
+    Ok(key)
+}
+
+/// Compares two sets of children, for the purposes of memoization. This is conservative: nodes
+/// are only considered equal if they share the same props (as in, the same allocation), since
+/// there's no way to compare arbitrary props otherwise. Each `rsx! {}` makes new props, so in
+/// practice only text children, or nodes passed down as-is, ever match.
+fn children_are_equal(old_children: &[RSX], new_children: &[RSX]) -> bool {
+    old_children.len() == new_children.len() && old_children.iter().zip(new_children).all(|pair| {
+        match pair {
+            (RSX::None, RSX::None) => true,
+            (RSX::VirtualText(old_text), RSX::VirtualText(new_text)) => old_text.0 == new_text.0,
+            (RSX::VirtualNode(old_node), RSX::VirtualNode(new_node)) => {
+                old_node.tag == new_node.tag
+                    && old_node.key == new_node.key
+                    && old_node.styles == new_node.styles
+                    && Arc::ptr_eq(&old_node.props, &new_node.props)
+                    && children_are_equal(&old_node.children, &new_node.children)
+            },
+            _ => false
+        }
+    })
+}
+
+/// Runs the update lifecycle for the `Component` at `key` (which is assumed to already have its
+/// latest props and children), re-rendering it and reconciling whatever it returns against its
+/// existing children. If `force` is `true`, `should_component_update()` is skipped.
+///
+/// `component_did_update()` isn't called here; it's recorded on the `Instance` (and as a
+/// `Patch::Update`), and called once the patches have been committed.
+fn update_component_tree(
+    key: ComponentKey,
+    force: bool,
+    prev_props: Arc<Any + Send + Sync>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let context = context_for(component_store.parent(key)?, component_store)?;
+    let instance = component_store.get_mut(key)?;
+    instance.component.receive_context(&context);
+    instance.consumed_context = context.into_consumed();
+    instance.component.get_derived_state_from_props();
+
+    if !force && !instance.component.should_component_update() {
+        return Ok(());
+    }
+
+    let provided_context = instance.component.provide_context();
+    let old_context = std::mem::replace(&mut instance.provided_context, provided_context.clone());
+    let rendered = render_component(&*instance.component, instance.children.clone());
+    let did_render = rendered.is_ok();
+
+    // Whatever was just rendered may measure differently.
+    if did_render {
+        update_measure_func(instance, layout_store)?;
+    }
+
+    // This has to happen before the children are reconciled, as that's what touches the
+    // backing layer tree.
+    let snapshot = if did_render {
+        instance.component.get_snapshot_before_update(&*prev_props)
+    } else {
+        None
+    };
+
+    let result = reconcile_rendered_children(key, rendered, component_store, layout_store, patches);
+
+    if let Some(caught) = caught_by_boundary(key, result)? {
+        reset_children(key, component_store, layout_store, patches)?;
+
+        let instance = component_store.get(key)?;
+        let rendered = render_component(&*instance.component, instance.children.clone());
+        reconcile_rendered_children(key, rendered, component_store, layout_store, patches)?;
+        patches.push(Patch::Catch { key, error: caught.error, info: caught.info });
+    }
+
+    // If the provided value changed, anything beneath here that read the old one needs to be
+    // re-rendered; it may well have been skipped above (e.g, by memoization).
+    if let Some(old_context) = old_context {
+        let is_changed = match &provided_context {
+            Some(provided_context) => !Arc::ptr_eq(&old_context, provided_context),
+            None => true
+        };
+
+        if is_changed {
+            update_context_consumers(key, &old_context, component_store, layout_store, patches)?;
+        }
+    }
+
+    // If this has already been updated since the last commit, the earlier props and snapshot
+    // are the ones that reflect what's actually on screen.
+    let instance = component_store.get_mut(key)?;
+    if did_render && instance.pending_update.is_none() {
+        instance.pending_update = Some(PendingUpdate { prev_props, snapshot });
+        patches.push(Patch::Update(key));
+    }
+
+    Ok(())
+}
+
+/// Builds the `Context` for a `Component` from the values provided by `parent` and its ancestors.
+fn context_for(parent: Option<ComponentKey>, component_store: &ComponentStore) -> Result<Context, Box<Error>> {
+    let mut values = vec![];
+    let mut ancestor = parent;
+
+    while let Some(key) = ancestor {
+        if let Some(value) = &component_store.get(key)?.provided_context {
+            values.push(value.clone());
+        }
+
+        ancestor = component_store.parent(key)?;
+    }
+
+    Ok(Context::new(values))
+}
+
+/// Walks the descendants of `key`, force updating any that read `old_context` (and haven't
+/// since been re-rendered with the new value).
+fn update_context_consumers(
+    key: ComponentKey,
+    old_context: &Arc<Any + Send + Sync>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    for child in component_store.children(key)? {
+        let instance = component_store.get(child)?;
+        if instance.consumed_context.iter().any(|value| Arc::ptr_eq(value, old_context)) {
+            let props = instance.props.clone();
+            update_component_tree(child, true, props, component_store, layout_store, patches)?;
+        }
+
+        update_context_consumers(child, old_context, component_store, layout_store, patches)?;
+    }
+
+    Ok(())
+}
+
+/// Reconciles the output of a `render()` call against the existing children of `key`, or
+/// handles the error if rendering failed.
+fn reconcile_rendered_children(
+    key: ComponentKey,
+    rendered: Result<RSX, Box<Error>>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    match rendered {
+        Ok(child) => reconcile_children(key, vec![child], component_store, layout_store, patches),
+        Err(e) => handle_render_error(key, e, component_store)
+    }
+}
+
+/// Mounts `new_tree` into the slot currently held by `key`, then unmounts the old tree. The new
+/// tree takes the same child index in the parent `Component`; if both the old and new trees
+/// resolve to a single native node, that node is swapped in place in the layout and native
+/// trees, and otherwise the native parent is re-linked.
+fn replace_component_tree(
+    key: ComponentKey,
+    new_tree: VirtualNode,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<ComponentKey, Box<Error>> {
+    let parent = component_store.parent(key)?;
+    let new_key = mount_component_tree(new_tree, parent, component_store, layout_store, patches)?;
+
+    if let Some(parent) = parent {
+        // Mounting appends the new tree to the parent; it gets moved into the old tree's slot
+        // below, once the old tree's native nodes are dealt with.
+        component_store.remove_child(parent, new_key)?;
+        let index = component_store.children(parent)?.iter().position(|child| *child == key)
+            .ok_or(RenderEngineError::InvalidComponentKey(key))?;
+
+        let mut old_native_children = vec![];
+        collect_native_children(key, component_store, &mut old_native_children)?;
+
+        let mut new_native_children = vec![];
+        collect_native_children(new_key, component_store, &mut new_native_children)?;
+
+        match (native_ancestor(parent, component_store)?, &old_native_children[..], &new_native_children[..]) {
+            (Some(native_parent), [old_child], [new_child]) => {
+                let parent_instance = component_store.get(native_parent)?;
+                let old_instance = component_store.get(*old_child)?;
+                let new_instance = component_store.get(*new_child)?;
+
+                let parent_layout = parent_instance.layout.ok_or(RenderEngineError::InvalidComponentKey(native_parent))?;
+                let old_layout = old_instance.layout.ok_or(RenderEngineError::InvalidComponentKey(*old_child))?;
+                let new_layout = new_instance.layout.ok_or(RenderEngineError::InvalidComponentKey(*new_child))?;
+                if let Some(index) = layout_store.children(parent_layout)?.iter().position(|child| *child == old_layout) {
+                    layout_store.replace_child_at_index(parent_layout, index, new_layout)?;
+                }
+
+                if let Some(old_node) = old_instance.component.borrow_native_backing_node() {
+                    patches.push(Patch::Replace { parent: native_parent, old: *old_child, old_node, new: *new_child });
+                }
+
+                component_store.replace_child_at_index(parent, index, new_key)?;
+            },
+
+            _ => {
+                detach_native_nodes(key, component_store, layout_store, patches)?;
+                component_store.replace_child_at_index(parent, index, new_key)?;
+                relink_native_children(parent, component_store, layout_store, patches)?;
+            }
+        }
+    }
+
+    unmount_component_tree(key, component_store, layout_store, patches)?;
+    Ok(new_key)
+}
+
+/// Given a parent `ComponentKey` and the new set of children for it, matches the new children
+/// against the existing ones and updates, mounts or unmounts accordingly. Children with a `key`
+/// are matched by that key, regardless of position; children without one are matched, in order,
+/// against the existing children that also have no key. Once the new order is known, the layout
+/// and native nodes are re-linked to match it.
+fn reconcile_children(
+    key: ComponentKey,
+    new_children: Vec<RSX>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let mut keyed = HashMap::new();
+    let mut unkeyed = VecDeque::new();
+
+    for old_child_key in component_store.children(key)? {
+        match &component_store.get(old_child_key)?.key {
+            Some(child_key) => { keyed.insert(child_key.clone(), old_child_key); },
+            None => { unkeyed.push_back(old_child_key); }
+        }
+    }
+
+    let mut children = vec![];
+    for new_child in flatten_fragments(new_children) {
+        let old_child_key = match &new_child.key {
+            Some(child_key) => keyed.remove(child_key),
+            None => unkeyed.pop_front()
+        };
+
+        match old_child_key {
+            // If there's an existing instance for this node, it's something we need to update -
+            // so let's recurse right back into it.
+            // If it's a different type of Component, it'll be replaced in the same slot.
+            Some(old_child_key) => {
+                children.push(recursively_diff_tree(old_child_key, RSX::VirtualNode(new_child), component_store, layout_store, patches)?);
+            },
+
+            // If there's no match, then we've got a new component instance to mount. This part
+            // now diverts into the Mount phase.
+            None => {
+                children.push(mount_component_tree(new_child, Some(key), component_store, layout_store, patches)?);
+            }
+        }
+    }
+
+    // Trim the fat. Anything we didn't match up is simply unmounted and dropped.
+    for old_child_key in keyed.values().chain(unkeyed.iter()) {
+        unmount_component_tree(*old_child_key, component_store, layout_store, patches)?;
+    }
+
+    component_store.set_children(key, children)?;
+    relink_native_children(key, component_store, layout_store, patches)
+}
+
+/// `Fragment`s only exist to hoist their children up a level, so wherever a list of children
+/// comes in, we flatten them out and discard anything that isn't a `VirtualNode` (text
+/// nodes are handled by the `Component` they're passed to, e.g `<Text>`).
+fn flatten_fragments(children: Vec<RSX>) -> Vec<VirtualNode> {
+    let mut nodes = vec![];
+
+    for child in children {
+        if let RSX::VirtualNode(child) = child {
+            if child.tag == "Fragment" {
+                nodes.append(&mut flatten_fragments(child.children));
+            } else {
+                nodes.push(child);
+            }
+        }
+    }
+
+    nodes
+}
+
+/// Given a new `RSX` tree, a `ComponentStore`, and a `LayoutStore`, will recursively construct the
+/// tree, emitting required lifecycle events and persisting values. This happens in an inward-out
+/// fashion, which helps avoid unnecessary reflow in environments where it can get tricky.
+///
+/// The new tree is added as the last child of `parent` before its children are mounted, so that
+/// errors can find their way to an error boundary; linking layout and native nodes into the
+/// parent is the responsibility of the caller. This method returns a Result, the `Ok` variant
+/// containing the `ComponentKey` for the newly mounted tree.
+fn mount_component_tree(
+    tree: VirtualNode,
+    parent: Option<ComponentKey>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<ComponentKey, Box<Error>> {
+    let key = component_store.new_key();
+    let mut component = (tree.create_component_fn)(key);
+    let is_native_backed = component.has_native_backing_node();
+    component.set_props(&*tree.props);
+
+    // A portal that can't reach its target fails to render, rather than attaching nowhere.
+    let (portal_target, portal_error) = match check_portal_target(component.portal_target(), component_store) {
+        Ok(portal_target) => (portal_target, None),
+        Err(e) => (None, Some(e))
+    };
+
+    let context = context_for(parent, component_store)?;
+    component.receive_context(&context);
+    component.get_derived_state_from_props();
+    
+    let mut instance = Instance {
+        tag: tree.tag,
+        key: tree.key,
+        node_ref: tree.node_ref,
+        tab_index: tree.tab_index,
+        event_handlers: tree.event_handlers,
+        style_keys: tree.styles,
+        component: component,
+        props: tree.props.clone(),
+        children: tree.children.clone(),
+        pending_update: None,
+        portal_target,
+        provided_context: None,
+        consumed_context: context.into_consumed(),
+        appearance: Appearance::default(),
+        layout: None,
+        last_applied_styles: None,
+        measured: None
+    };
+
+    if is_native_backed {
+        let mut style = Style::default();
+        THEME_ENGINE.configure_styles_for_keys(&instance.style_keys, &mut style, &mut instance.appearance);
+        instance.layout = Some(layout_store.new_node(style, vec![])?);
+    }
+    
+    instance.provided_context = instance.component.provide_context();
+    let rendered = match portal_error {
+        Some(e) => Err(e),
+        None => render_component(&*instance.component, tree.children)
+    };
+
+    update_measure_func(&mut instance, layout_store)?;
+    component_store.insert(key, instance)?;
+    if let Some(parent) = parent {
+        component_store.add_child(parent, key)?;
+    }
+
+    if portal_target.is_some() {
+        component_store.add_portal(key);
+    }
+
+    let result = mount_rendered_children(key, rendered, component_store, layout_store, patches);
+
+    if let Some(caught) = caught_by_boundary(key, result)? {
+        reset_children(key, component_store, layout_store, patches)?;
+
+        let instance = component_store.get(key)?;
+        let rendered = render_component(&*instance.component, instance.children.clone());
+        mount_rendered_children(key, rendered, component_store, layout_store, patches)?;
+        patches.push(Patch::Catch { key, error: caught.error, info: caught.info });
+    }
+
+    // Nothing above this will link a portal's children, as they live under another root.
+    if portal_target.is_some() {
+        relink_native_children(key, component_store, layout_store, patches)?;
+    }
+
+    let instance_lol = component_store.get_mut(key)?;
+    if let Some(node_ref) = &instance_lol.node_ref {
+        patches.push(Patch::AttachRef { key, node_ref: node_ref.clone() });
+    }
+
+    if let Some(text) = text_content(instance_lol) {
+        patches.push(Patch::SetText { key, text });
+    }
+
+    if let Some(properties) = accessibility_properties(instance_lol) {
+        patches.push(Patch::SetAccessibility { key, properties });
+    }
+
+    patches.push(Patch::Create(key));
+
+    Ok(key)
+}
+
+/// Mounts the output of a `render()` call as the children of `key`, or handles the error if
+/// rendering failed.
+fn mount_rendered_children(
+    key: ComponentKey,
+    rendered: Result<RSX, Box<Error>>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let child = match rendered {
+        Ok(child) => child,
+        Err(e) => { return handle_render_error(key, e, component_store); }
+    };
+
+    let is_native_backed = component_store.get(key)?.layout.is_some();
+
+    // We want to support Components being able to return arbitrary iteratable
+    // elements, but... well, it's not quite that simple. Thus we'll offer a <Fragment>
+    // tag similar to what React does, which just hoists the children out of it and
+    // discards the rest.
+    for child_tree in flatten_fragments(vec![child]) {
+        let child_key = mount_component_tree(child_tree, Some(key), component_store, layout_store, patches)?;
+        
+        if is_native_backed {
+            link_layout_nodess(key, child_key, component_store, layout_store, patches)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Calls `render()` on a `Component`, turning a panic into an `Err` so that it can be handled
+/// like any other render error.
+fn render_component(component: &Component, children: Vec<RSX>) -> Result<RSX, Box<Error>> {
+    match panic::catch_unwind(AssertUnwindSafe(|| component.render(children))) {
+        Ok(rendered) => rendered,
+
+        Err(payload) => {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => match payload.downcast_ref::<String>() {
+                    Some(message) => message.clone(),
+                    None => "Unknown panic".into()
+                }
+            };
+
+            Err(Box::new(RenderEngineError::ComponentPanicked(message)))
+        }
+    }
+}
+
+/// Handles a failed `render()` for the `Component` at `key`. This walks up the tree looking for
+/// the nearest error boundary (an ancestor whose `get_derived_state_from_error()` returns `true`)
+/// and, if it finds one, returns an `Err` that unwinds the render pass back up to it. If there's
+/// no boundary, the error itself is returned, ending the render pass (see `diff_root()`).
+fn handle_render_error(
+    key: ComponentKey,
+    error: Box<Error>,
+    component_store: &mut ComponentStore
+) -> Result<(), Box<Error>> {
+    let mut component_stack = vec![];
+    let mut ancestor = Some(key);
+    while let Some(component_key) = ancestor {
+        component_stack.push(component_store.get(component_key)?.tag);
+        ancestor = component_store.parent(component_key)?;
+    }
+    component_stack.reverse();
+
+    let mut ancestor = component_store.parent(key)?;
+    while let Some(boundary) = ancestor {
+        if component_store.get_mut(boundary)?.component.get_derived_state_from_error(&error) {
+            return Err(Box::new(CaughtError {
+                boundary,
+                error,
+                info: ErrorInfo { component_stack }
+            }));
+        }
+
+        ancestor = component_store.parent(boundary)?;
+    }
+
+    Err(error)
+}
+
+/// Checks whether `result` is an error that's unwinding back to the error boundary at `key`. If
+/// it is, the `Ok` variant holds it (with the original error and its `ErrorInfo`); any other
+/// error is passed along.
+fn caught_by_boundary(
+    key: ComponentKey,
+    result: Result<(), Box<Error>>
+) -> Result<Option<CaughtError>, Box<Error>> {
+    let error = match result {
+        Ok(_) => { return Ok(None); },
+        Err(error) => error
+    };
+
+    let is_for_key = match error.downcast_ref::<CaughtError>() {
+        Some(caught) => caught.boundary == key,
+        None => false
+    };
+
+    if !is_for_key {
+        return Err(error);
+    }
+
+    Ok(Some(*error.downcast::<CaughtError>().unwrap()))
+}
+
+/// Tears down everything beneath `key`, so an error boundary can render its fallback UI from
+/// a clean slate.
+fn reset_children(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    for child in component_store.children(key)? {
+        unmount_component_tree(child, component_store, layout_store, patches)?;
+    }
+
+    if let Some(layout) = component_store.get(key)?.layout {
+        layout_store.set_children(layout, vec![])?;
+    }
+
+    Ok(())
+}
+
+/// Given a `ComponentKey`, a `ComponentStore`, and a `LayoutStore`, will recursively walk the tree found at
+/// said key, taking it out of the tree and recording an `Unmount` for each `Component` (which is
+/// when it's told, and dropped). This happens in an inward-out fashion, so deepest
+/// nodes/components get destroyed first to ensure that the backing widget tree doesn't get some
+/// weird dangling issue.
+///
+/// The top-most native nodes of the tree are detached from the backing tree up front; anything
+/// beneath them goes along with them.
+fn unmount_component_tree(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    detach_native_nodes(key, component_store, layout_store, patches)?;
+    teardown_component_tree(key, component_store, layout_store, patches)
+}
+
+/// Does the actual work for `unmount_component_tree()`, once the tree has been detached.
+fn teardown_component_tree(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let instance = component_store.get(key)?;
+    let layout = instance.layout;
+    let children = component_store.children(key)?;
+
+    // A portal's children are attached under its target, so they need detaching separately.
+    if instance.portal_target.is_some() {
+        for child in &children {
+            detach_native_nodes(*child, component_store, layout_store, patches)?;
+        }
+    }
+
+    // A child that fails to come down shouldn't keep its siblings (or this) mounted; the first
+    // error is returned once everything else is torn down.
+    let mut torn_down = Ok(());
+    for child in children {
+        let result = teardown_component_tree(child, component_store, layout_store, patches);
+        if torn_down.is_ok() {
+            torn_down = result;
+        }
+    }
+
+    component_store.unmount(key)?;
+    patches.push(Patch::Unmount(key));
+
+    // Not every child may have been linked (e.g, if mounting was cut short by an error), so
+    // rather than removing them one by one, just clear them all out.
+    if let Some(layout_node) = layout {
+        layout_store.set_children(layout_node, vec![])?;
+    }
+
+    torn_down
+}
+
+/// Removes the top-most native nodes at or beneath `key` from their native parent, in both the
+/// layout and backing trees.
+fn detach_native_nodes(
+    key: ComponentKey,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let native_parent = match components.parent(key)? {
+        Some(parent) => native_ancestor(parent, components)?,
+        None => None
+    };
+
+    let native_parent = match native_parent {
+        Some(native_parent) => native_parent,
+        None => { return Ok(()); }
+    };
+
+    let mut native_children = vec![];
+    collect_native_children(key, components, &mut native_children)?;
+
+    let parent_instance = components.get(native_parent)?;
+    let parent_layout = parent_instance.layout.unwrap();
+    for child in native_children {
+        let child_instance = components.get(child)?;
+        let child_layout = child_instance.layout.unwrap();
+
+        // Mounting may have been cut short before this was linked.
+        if layouts.children(parent_layout)?.contains(&child_layout) {
+            layouts.remove_child(parent_layout, child_layout)?;
+        }
+
+        if let Some(node) = child_instance.component.borrow_native_backing_node() {
+            patches.push(Patch::Remove { parent: native_parent, child, node });
+        }
+    }
+
+    Ok(())
+}
+
+/// Given a tree, will walk the branches until it finds the next root nodes to connect.
+/// While this sounds slow, in practice it rarely has to go far in any direction. This could 
+/// potentially be done away with some hoisting magic in the `mount()` recursion, but I couldn't 
+/// find a pattern that didn't feel like some utter magic in Rust.
+///
+/// It might be because I'm writing this at 3AM. Feel free to improve it.
+fn link_layout_nodess(
+    parent: ComponentKey,
+    child: ComponentKey,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    if let (Ok(parent_instance), Ok(child_instance)) = (components.get(parent), components.get(child)) {
+        if let (Some(parent_layout), Some(child_layout)) = (parent_instance.layout, child_instance.layout) {
+            layouts.add_child(parent_layout, child_layout)?;
+            let index = layouts.child_count(parent_layout)? - 1;
+            patches.push(Patch::InsertAt { parent, child, index });
+
+            return Ok(());
+        }
+
+        // A portal's children are linked under its target instead.
+        if child_instance.portal_target.is_some() {
+            return Ok(());
+        }
+    }
+
+    let children = components.children(child)?;
+    for child_key in children {
+        link_layout_nodess(parent, child_key, components, layouts, patches)?;
+    }
+
+    Ok(())
+}
+
+/// After the children of a `Component` have been reconciled, the layout and native trees need
+/// to be brought in line with the new order. Since non-native `Component`s don't have a node
+/// in either tree, this walks up to the nearest native-backed ancestor, gathers the native
+/// children beneath it (in order), and re-links from the first point where they differ.
+fn relink_native_children(
+    key: ComponentKey,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let parent = match native_ancestor(key, components)? {
+        Some(parent) => parent,
+        None => { return Ok(()); }
+    };
+
+    let children = native_children(parent, components)?;
+    let parent_instance = components.get(parent)?;
+    let parent_layout = parent_instance.layout.unwrap();
+    let mut child_layouts = vec![];
+    for child in &children {
+        child_layouts.push(components.get(*child)?.layout.unwrap());
+    }
+
+    let mut existing_layouts = layouts.children(parent_layout)?;
+    if existing_layouts == child_layouts {
+        return Ok(());
+    }
+
+    layouts.set_children(parent_layout, child_layouts.clone())?;
+
+    // Anything that's been removed has already been detached, so walking the new order and
+    // inserting whatever isn't already in place (which moves it, if it's elsewhere) leaves the
+    // backing tree matching.
+    for (index, child) in children.iter().enumerate() {
+        let child_layout = child_layouts[index];
+        if existing_layouts.get(index) == Some(&child_layout) {
+            continue;
+        }
+
+        existing_layouts.retain(|layout| *layout != child_layout);
+        existing_layouts.insert(index, child_layout);
+        patches.push(Patch::InsertAt { parent, child: *child, index });
+    }
+
+    Ok(())
+}
+
+/// Checks that a portal's `target` is somewhere its children can be attached: a `Component` in
+/// the same `ComponentStore` (and so, the same `RenderEngine`).
+fn check_portal_target(
+    target: Option<ComponentKey>,
+    components: &ComponentStore
+) -> Result<Option<ComponentKey>, Box<Error>> {
+    if let Some(target) = target {
+        if target.instance != components.id() {
+            return Err(Box::new(RenderEngineError::PortalTargetInAnotherEngine(target)));
+        }
+
+        components.get(target)?;
+    }
+
+    Ok(target)
+}
+
+/// Walks up from `key` (inclusive) to find the nearest native-backed `Component`. For anything
+/// beneath a portal, this is the portal's target.
+fn native_ancestor(key: ComponentKey, components: &ComponentStore) -> Result<Option<ComponentKey>, Box<Error>> {
+    let mut key = key;
+    while components.get(key)?.layout.is_none() {
+        if let Some(portal_target) = components.get(key)?.portal_target {
+            return Ok(components.get(portal_target).ok().map(|_| portal_target));
+        }
+
+        key = match components.parent(key)? {
+            Some(parent) => parent,
+            None => { return Ok(None); }
+        };
+    }
+
+    Ok(Some(key))
+}
+
+/// Returns the native children of the natively backed `parent`, in order. Anything portaled in
+/// goes after the parent's own children.
+fn native_children(parent: ComponentKey, components: &ComponentStore) -> Result<Vec<ComponentKey>, Box<Error>> {
+    let mut children = vec![];
+    for child in components.children(parent)? {
+        collect_native_children(child, components, &mut children)?;
+    }
+
+    for portal in components.portals_targeting(parent)? {
+        for child in components.children(portal)? {
+            collect_native_children(child, components, &mut children)?;
+        }
+    }
+
+    Ok(children)
+}
+
+/// Collects the top-most native-backed `Component`s at or beneath `key`, in order. Portals are
+/// skipped, as their children belong to their target.
+fn collect_native_children(
+    key: ComponentKey,
+    components: &ComponentStore,
+    native_children: &mut Vec<ComponentKey>
+) -> Result<(), Box<Error>> {
+    let instance = components.get(key)?;
+    if instance.layout.is_some() {
+        native_children.push(key);
+        return Ok(());
+    }
+
+    if instance.portal_target.is_some() {
+        return Ok(());
+    }
+
+    for child in components.children(key)? {
+        collect_native_children(child, components, native_children)?;
+    }
+
+    Ok(())
+}
+
+/// Computes layout for the root at `key`, given the `available` space, and then walks the tree
+/// to record whatever changed as patches.
+///
+/// Only nodes whose style, children or content changed are marked dirty (which also marks their
+/// ancestors), and everything else keeps its cached layout - so this only recomputes the paths
+/// leading to a change, and skips computing entirely if nothing changed.
+fn compute_layout(
+    key: ComponentKey,
+    available: Size<Number>,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let layout_node = components.get(key)?.layout.unwrap();
+    if layouts.dirty(layout_node)? {
+        layouts.compute_layout(layout_node, available)?;
+    }
+
+    diff_styles(key, components, layouts, patches)?;
+
+    // Anything in this tree that's portaled into another root changes that root's layout, too.
+    for target in portal_targets_within(key, components)? {
+        let target_layout = components.get(target)?.layout.unwrap();
+        if layouts.dirty(target_layout)? {
+            let size = layouts.layout(target_layout)?.size;
+            layouts.compute_layout(target_layout, Size {
+                width: Number::Defined(size.width),
+                height: Number::Defined(size.height)
+            })?;
+        }
+
+        diff_styles(target, components, layouts, patches)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the roots (other than `key` itself) that portals in the tree at `key` point into.
+fn portal_targets_within(key: ComponentKey, components: &ComponentStore) -> Result<Vec<ComponentKey>, Box<Error>> {
+    let mut targets = vec![];
+
+    for portal in components.portals() {
+        let target = components.get(portal)?.portal_target.unwrap();
+        if target == key || targets.contains(&target) || components.get(target).is_err() {
+            continue;
+        }
+
+        let mut root = portal;
+        while let Some(parent) = components.parent(root)? {
+            root = parent;
+        }
+
+        if root == key {
+            targets.push(target);
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Walks the tree and records the Layout and Appearance-based styles that Components need to pass
+/// to their backing widgets. This happens after a layout computation, typically. Only what's
+/// changed since styles were last applied is recorded.
+fn diff_styles(
+    key: ComponentKey,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore,
+    patches: &mut Vec<Patch>
+) -> Result<(), Box<Error>> {
+    let instance = components.get(key)?;
+
+    if let Some(layout_key) = instance.layout {
+        let layout = *layouts.layout(layout_key)?;
+        let appearance = instance.appearance;
+        let (last_layout, last_appearance) = match instance.last_applied_styles {
+            Some((last_layout, last_appearance)) => (Some(last_layout), Some(last_appearance)),
+            None => (None, None)
+        };
+
+        if last_layout != Some(layout) {
+            patches.push(Patch::SetFrame { key, layout });
+        }
+
+        if last_appearance != Some(appearance) {
+            patches.push(Patch::SetAppearance { key, appearance });
+        }
+    }
 
-pub struct RenderEngine {
-    queued_state_updates: Mutex<Vec<i32>>,
-    components: Mutex<ComponentStore>,
-    layouts: Mutex<LayoutStore>
-}
+    // Portals are walked from their target, since that's where they're laid out.
+    for child in components.children(key)? {
+        if components.get(child)?.portal_target.is_none() {
+            diff_styles(child, components, layouts, patches)?;
+        }
+    }
 
-impl RenderEngine {
-    pub(crate) fn new() -> RenderEngine {
-        RenderEngine {
-            queued_state_updates: Mutex::new(vec![]),
-            components: Mutex::new(ComponentStore::new()),
-            layouts: Mutex::new(LayoutStore::new())
+    for portal in components.portals_targeting(key)? {
+        for child in components.children(portal)? {
+            diff_styles(child, components, layouts, patches)?;
         }
     }
 
-    // pub fn queue_update_for(&self, component_ptr: usize, updater: Box<Fn() -> Component + Send + Sync + 'static>) {
-    // }
+    Ok(())
+}
 
-    /// `Window`'s (or anything "root" in nature) need to register with the 
-    /// reconciler for things like setState to work properly. When they do so,
-    /// they get a key back. When they want to instruct the global `RenderEngine` 
-    /// to re-render or update their tree, they pass that key and whatever the new tree 
-    /// should be.
-    pub fn register_root_component<C: Component + 'static>(&self, component: C) -> Result<ComponentKey, Box<Error>> {
-        // Conceivably, this doesn't NEED to be a thing... but for now it is. If you've stumbled
-        // upon here, wayward traveler, in need of a non-native-root-component, please open an
-        // issue to discuss. :)
-        if !component.has_native_backing_node() {
-            return Err(Box::new(RenderEngineError::InvalidRootComponent {}));
-        }
+/// Runs the commit phase, in three passes:
+///
+/// - Changes to the backing tree (inserts, removals, text, accessibility) are applied in order,
+///   `Ref`s that have moved on are cleared, and unmounted `Component`s are told
+///   (`component_will_unmount()`) and dropped (clearing their `Ref`) as their `Unmount` comes up.
+/// - Frames and appearances are gathered up and applied, so that each `Component` has its
+///   styles applied once.
+/// - `Ref`s are attached, and `component_did_mount()`, `component_did_update()` and
+///   `component_did_catch()` are called, in the order they were recorded in, which is children
+///   first (so a `Component`'s `Ref` resolves before its parent hears that it's mounted).
+///
+/// This mirrors React, where the host tree is mutated before any layout effects run: by the
+/// time a `Component` hears that it's mounted or updated, its native node is in place and has
+/// its final frame.
+///
+/// `native_nodes` (which native node belongs to which `Component`, for routing events) is kept
+/// up to date as `Create`s and `Unmount`s are applied.
+fn apply_patches(
+    patches: Vec<Patch>,
+    components: &mut ComponentStore,
+    native_nodes: &mut HashMap<usize, ComponentKey>
+) {
+    let mut styles: Vec<(ComponentKey, Option<Layout>, Option<Appearance>)> = vec![];
+    let mut lifecycle = vec![];
 
-        let mut component_store = self.components.lock().unwrap();
-        let mut layouts_store = self.layouts.lock().unwrap();
-        let component_key = component_store.new_key();
-        component_store.insert(component_key, Instance {
-            tag: "root",
-            style_keys: "root".into(),
-            component: Box::new(component),
-            appearance: Appearance::default(),
-            layout: Some(layouts_store.new_node(Style::default(), vec![])?)
-        })?;
+    // Something that's mounted and then unmounted within the same render (e.g, beneath an error
+    // boundary that reset) never heard that it was mounted, so it doesn't hear that it's not.
+    let created: Vec<ComponentKey> = patches.iter().filter_map(|patch| match patch {
+        Patch::Create(key) => Some(*key),
+        _ => None
+    }).collect();
 
-        Ok(component_key)
-    }
+    for patch in patches {
+        match patch {
+            Patch::InsertAt { parent, child, index } => {
+                if let (Ok(parent), Ok(child)) = (components.get(parent), components.get(child)) {
+                    if let Some(node) = child.component.borrow_native_backing_node() {
+                        parent.component.insert_child_node_at(node, index);
+                    }
+                }
+            },
 
-    /// Rendering the root node is a bit different than rendering or updating other nodes, as we 
-    /// never want to unmount it, and the results come from a non-`Component` entity (e.g, a
-    /// `Window`). Thus, for this one, we do some manual mucking with what we know is the
-    /// root view (a `Window` or such root component would call this with it's registered
-    /// `ComponentKey`), and then recurse based on the children.
-    pub fn diff_and_render_root(
-        &self,
-        key: ComponentKey,
-        dimensions: (f64, f64),
-        child: RSX
-    ) -> Result<(), Box<Error>> {
-        let mut component_store = self.components.lock().unwrap();
-        let mut layout_store = self.layouts.lock().unwrap();
+            Patch::Remove { parent, node, .. } => {
+                if let Ok(parent) = components.get(parent) {
+                    parent.component.remove_child_node(node);
+                }
+            },
 
-        let new_root_node = RSX::node("root", "root".into(), |_| {
-            Box::new(GenericRootView {})
-        }, Box::new(GenericRootViewProps {}), match child {
-            RSX::VirtualNode(node) => {
-                if node.tag == "Fragment" {
-                    node.children
-                } else {
-                    vec![RSX::VirtualNode(node)]
+            Patch::Replace { parent, old_node, new, .. } => {
+                if let (Ok(parent), Ok(new)) = (components.get(parent), components.get(new)) {
+                    if let Some(node) = new.component.borrow_native_backing_node() {
+                        parent.component.replace_child_node(old_node, node);
+                    }
                 }
             },
 
-            _ => vec![]
-        });
+            Patch::SetText { key, text } => {
+                if let Ok(instance) = components.get(key) {
+                    instance.component.apply_text(&text);
+                }
+            },
+
+            Patch::SetAccessibility { key, properties } => {
+                if let Ok(instance) = components.get(key) {
+                    instance.component.apply_accessibility(&properties);
+                }
+            },
 
-        recursively_diff_tree(key, new_root_node, &mut component_store, &mut layout_store)?;
+            Patch::DetachRef { key, node_ref } => { node_ref.detach(key); },
 
-        let layout_node = {
-            let mut root_instance = component_store.get_mut(key)?;
-            let layout = root_instance.layout.unwrap();
-            let mut style = Style::default();
-            THEME_ENGINE.configure_styles_for_keys(&root_instance.style_keys, &mut style, &mut root_instance.appearance);
-            style.size = Size {
-                width: Dimension::Points(dimensions.0 as f32),
-                height: Dimension::Points(dimensions.1 as f32)
-            };
-            layout_store.set_style(layout, style);
-            layout
+            Patch::Unmount(key) => {
+                if let Ok(mut instance) = components.take_unmounted(key) {
+                    if let Some(node) = instance.component.borrow_native_backing_node() {
+                        native_nodes.remove(&native_node_id(&node));
+                    }
+
+                    if !created.contains(&key) {
+                        instance.component.component_will_unmount();
+                    }
+
+                    if let Some(node_ref) = &instance.node_ref {
+                        node_ref.detach(key);
+                    }
+                }
+            },
+
+            Patch::SetFrame { key, layout } => match styles.iter_mut().find(|entry| entry.0 == key) {
+                Some(entry) => { entry.1 = Some(layout); },
+                None => { styles.push((key, Some(layout), None)); }
+            },
+
+            Patch::SetAppearance { key, appearance } => match styles.iter_mut().find(|entry| entry.0 == key) {
+                Some(entry) => { entry.2 = Some(appearance); },
+                None => { styles.push((key, None, Some(appearance))); }
+            },
+
+            Patch::Create(key) => {
+                if let Ok(instance) = components.get(key) {
+                    if let Some(node) = instance.component.borrow_native_backing_node() {
+                        native_nodes.insert(native_node_id(&node), key);
+                    }
+                }
+
+                lifecycle.push(patch);
+            },
+
+            Patch::AttachRef { .. } | Patch::Update(_) | Patch::Catch { .. } => { lifecycle.push(patch); }
+        }
+    }
+
+    for (key, layout, appearance) in styles {
+        let instance = match components.get_mut(key) {
+            Ok(instance) => instance,
+            Err(_) => { continue; }
         };
 
-        layout_store.compute_layout(layout_node, Size {
-            width: Number::Defined(dimensions.0 as f32),
-            height: Number::Defined(dimensions.1 as f32)
-        })?;
-        
-        walk_and_apply_styles(key, &mut component_store, &mut layout_store)?;
+        let last_applied = instance.last_applied_styles;
+        let layout = layout.or_else(|| last_applied.map(|styles| styles.0));
+        let appearance = appearance.or_else(|| last_applied.map(|styles| styles.1));
 
-        Ok(())
+        if let (Some(layout), Some(appearance)) = (layout, appearance) {
+            instance.component.apply_styles(&appearance, &layout);
+            instance.last_applied_styles = Some((layout, appearance));
+        }
+    }
+
+    for patch in lifecycle {
+        match patch {
+            // Whatever was mounted and then unmounted within this render has nothing to attach to.
+            Patch::AttachRef { key, ref node_ref } if components.get(key).is_ok() => {
+                node_ref.attach(key);
+            },
+
+            Patch::Create(key) => {
+                if let Ok(instance) = components.get_mut(key) {
+                    instance.component.component_did_mount();
+                }
+            },
+
+            Patch::Update(key) => {
+                if let Ok(instance) = components.get_mut(key) {
+                    if let Some(pending) = instance.pending_update.take() {
+                        instance.component.component_did_update(&*pending.prev_props, pending.snapshot);
+                    }
+                }
+            },
+
+            Patch::Catch { key, error, info } => {
+                if let Ok(instance) = components.get_mut(key) {
+                    instance.component.component_did_catch(&error, &info);
+                }
+            },
+
+            _ => {}
+        }
     }
 }
 
-/// Given two trees, will diff them to see if we need to replace or update. Depending on the
-/// result, we'll either recurse down a level, or tear down and build up a new tree. The final
-/// parameter on this method, `is_root_entity_view`, should only be passed for `Window` or other
-/// such instances, as it instructs us to skip the first level since these ones act different.
-fn recursively_diff_tree(
+/// Recursively builds an `InspectorNode` for the tree found at `key`.
+fn inspect_component_tree(
     key: ComponentKey,
-    new_tree: RSX,
-    component_store: &mut ComponentStore,
-    layout_store: &mut LayoutStore
-) -> Result<(), Box<Error>> {
-    // First we need to determine if this node is being replaced or updated. A replace happens if 
-    // two nodes are different types - in this case, we check their tag values. This is also a case
-    // where, for instance, if the RSX tag is `::None` or `::VirtualText`, we'll treat it as
-    // replacing with nothing.
-    let is_replace = match &new_tree {
-        RSX::VirtualNode(new_tree) => {
-            let old_tree = component_store.get(key)?;
-            old_tree.tag != new_tree.tag
-        },
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<InspectorNode, Box<Error>> {
+    let instance = components.get(key)?;
 
-        // The algorithm will know below not to recurse if we're trying to diff text or empty
-        // values. We return false here to avoid entering the `is_replace` phase; `Component`
-        // instances (like <Text />) handle taking the child VirtualText instances and working with
-        // them to pass to a native widget.
-        _ => false
+    let (style, layout) = match instance.layout {
+        Some(layout) => (Some(*layouts.style(layout)?), Some(*layouts.layout(layout)?)),
+        None => (None, None)
     };
 
-    if is_replace {
-        unmount_component_tree(key, component_store, layout_store)?;
-        //mount_component_tree(
-        return Ok(());
+    let mut children = vec![];
+    for child in components.children(key)? {
+        children.push(inspect_component_tree(child, components, layouts)?);
     }
 
-    // At this point, we know it's an update pass. Now we need to do a few things:
-    //
-    // - Diff our `props` and figure out what actions we can take or shortcut.
-    // - Let the `Component` instance determine what it should render.
-    // - Recurse into the child trees if necessary.
-    let mut old_children = component_store.children(key)?;
-    old_children.reverse();
-
-    if let RSX::VirtualNode(mut child) = new_tree {
-        for new_child_tree in child.children {
-            match old_children.pop() {
-                // If there's a key in the old children for this position, it's 
-                // something we need to update, so let's recurse right back into it.
-                Some(old_child_key) => {
-                    recursively_diff_tree(
-                        old_child_key,
-                        new_child_tree,
-                        component_store,
-                        layout_store
-                    )?;
-                },
+    Ok(InspectorNode {
+        tag: instance.tag,
+        style_keys: instance.style_keys.iter().map(ToString::to_string).collect(),
+        style,
+        appearance: instance.appearance,
+        layout,
+        text: text_content(instance),
+        is_native_backed: instance.component.has_native_backing_node(),
+        children
+    })
+}
 
-                // If there's no matching old key in this position, then we've got a 
-                // new component instance to mount. This part now diverts into the Mount
-                // phase.
-                None => {
-                    if let RSX::VirtualNode(tr33amimustfeelohlol) = new_child_tree {
-                        let new_child_key = mount_component_tree(
-                            tr33amimustfeelohlol,
-                            component_store,
-                            layout_store
-                        )?;
-
-                        component_store.add_child(key, new_child_key)?;
-                        link_layout_nodess(key, new_child_key, component_store, layout_store)?;
-                    }
-                }
-            }
+/// Returns the children of `key` as they're displayed, rather than as they're declared: a
+/// `Portal`'s content isn't displayed where the portal is, but after its target's own children.
+fn displayed_children(key: ComponentKey, components: &ComponentStore) -> Result<Vec<ComponentKey>, Box<Error>> {
+    let mut children = vec![];
+    for child in components.children(key)? {
+        if components.get(child)?.portal_target.is_none() {
+            children.push(child);
         }
     }
 
-    // Trim the fat. If we still have child nodes after diffing in the new child trees,
-    // then they're ones that simply need to be unmounted and dropped.
-    if old_children.len() > 0 {
-        for child in old_children {
-            unmount_component_tree(child, component_store, layout_store)?;
+    if components.get(key)?.layout.is_some() {
+        for portal in components.portals_targeting(key)? {
+            children.append(&mut components.children(portal)?);
         }
     }
 
-    Ok(())
+    Ok(children)
 }
 
-/// Given a new `RSX` tree, a `ComponentStore`, and a `LayoutStore`, will recursively construct the
-/// tree, emitting required lifecycle events and persisting values. This happens in an inward-out
-/// fashion, which helps avoid unnecessary reflow in environments where it can get tricky.
-///
-/// This method returns a Result, the `Ok` variant containing a tuple of Vecs. These are the child 
-/// Component instances and Layout instances that need to be set in the stores.
-fn mount_component_tree(
-    tree: VirtualNode,
-    component_store: &mut ComponentStore,
-    layout_store: &mut LayoutStore
-) -> Result<ComponentKey, Box<Error>> {
-    let key = component_store.new_key();
-    let component = (tree.create_component_fn)(key);
-    let is_native_backed = component.has_native_backing_node();
-    
-    // let state = get_derived_state_from_props()
-    let mut instance = Instance {
-        tag: tree.tag,
-        style_keys: tree.styles,
-        component: component,
-        appearance: Appearance::default(),
-        layout: None
+/// Hands the layout node for `instance` a new measure function, if what it measures (its text,
+/// and the font it's set in) has changed since the last one. Setting one marks the node dirty,
+/// so it'll be picked up on the next layout pass - which is why this doesn't happen otherwise.
+fn update_measure_func(instance: &mut Instance, layout_store: &mut LayoutStore) -> Result<(), Box<Error>> {
+    let layout = match instance.layout {
+        Some(layout) => layout,
+        None => { return Ok(()); }
     };
 
-    if is_native_backed {
-        let mut style = Style::default();
-        THEME_ENGINE.configure_styles_for_keys(&instance.style_keys, &mut style, &mut instance.appearance);
-        instance.layout = Some(layout_store.new_node(style, vec![])?);
+    let content = MeasuredContent::new(text_content(instance), &instance.appearance);
+    if instance.measured.as_ref() == Some(&content) {
+        return Ok(());
     }
-    
-    let rendered = instance.component.render(tree.children);
-    // instance.get_snapshot_before_update()
-    component_store.insert(key, instance)?;
-
-    match rendered {
-        Ok(child) => if let RSX::VirtualNode(child) = child {
-            // We want to support Components being able to return arbitrary iteratable
-            // elements, but... well, it's not quite that simple. Thus we'll offer a <Fragment>
-            // tag similar to what React does, which just hoists the children out of it and
-            // discards the rest.
-            if child.tag == "Fragment" {
-                for child_tree in child.children {
-                    if let RSX::VirtualNode(child_tree) = child_tree {
-                        let child_key = mount_component_tree(child_tree, component_store, layout_store)?;
-                        
-                        component_store.add_child(key, child_key)?;
-                        if is_native_backed {
-                            link_layout_nodess(key, child_key, component_store, layout_store)?;
-                        }
-                    }
-                }
-            } else {
-                let child_key = mount_component_tree(child, component_store, layout_store)?;
-                
-                component_store.add_child(key, child_key)?;
-                if is_native_backed {
-                    link_layout_nodess(key, child_key, component_store, layout_store)?;
-                }
-            }
-        },
 
-        Err(e) => {
-            // return an RSX::VirtualNode(ErrorComponentView) or something?
-            /* instance.get_derived_state_from_error(e) */
-            // render error state or something I guess?
-            /* instance.component_did_catch(e, info) */
-            eprintln!("Error rendering: {}", e);
-        }
+    if let Some(measure) = instance.component.measure_func(&instance.appearance) {
+        layout_store.set_measure(layout, Some(measure))?;
+        instance.measured = Some(content);
     }
 
-    let instance_lol = component_store.get_mut(key)?;
-    instance_lol.component.component_did_mount();
+    Ok(())
+}
 
-    Ok(key)
+/// Returns any text passed to `instance` as children (e.g, for a `Text`).
+fn text_content(instance: &Instance) -> Option<String> {
+    let text: String = instance.children.iter().filter_map(|child| match child {
+        RSX::VirtualText(text) => Some(text.0.as_str()),
+        _ => None
+    }).collect();
+
+    if text.is_empty() { None } else { Some(text) }
 }
 
-/// Given a `ComponentKey`, a `ComponentStore`, and a `LayoutStore`, will recursively walk the tree found at
-/// said key, emitting required lifecycle events and dropping values. This happens in an inward-out
-/// fashion, so deepest nodes/components get destroyed first to ensure that the backing widget tree
-/// doesn't get some weird dangling issue.
-fn unmount_component_tree(
+/// Builds the accessibility node for `key` with the given `properties`. `origin` is where the
+/// native node that `key` is laid out in sits, relative to the root of the tree.
+fn accessibility_node(
     key: ComponentKey,
-    component_store: &mut ComponentStore,
-    layout_store: &mut LayoutStore
-) -> Result<Vec<LayoutNode>, Box<Error>> {
-    let mut instance = component_store.remove(key)?;
-    instance.component.component_will_unmount();
-    
-    let mut layout_nodes = vec![];
-    
-    let children = component_store.children(key)?;
-    for child in children {
-        match unmount_component_tree(child, component_store, layout_store) {
-            Ok(mut child_layout_nodes) => {
-                if let Some(parent_layout_node) = instance.layout {
-                    for node in child_layout_nodes {
-                        layout_store.remove_child(parent_layout_node, node)?;
-                    }
-                } else {
-                    layout_nodes.append(&mut child_layout_nodes);
-                }
-            },
+    properties: AccessibilityProperties,
+    origin: Point<f32>,
+    focused: Option<ComponentKey>,
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<AccessibilityNode, Box<Error>> {
+    let instance = components.get(key)?;
 
-            Err(e) => { eprintln!("Error unmounting a component tree: {}", e); }
-        }
+    let frame = match instance.layout {
+        Some(layout) => {
+            let layout = layouts.layout(layout)?;
+            Some(Frame {
+                x: origin.x + layout.location.x,
+                y: origin.y + layout.location.y,
+                width: layout.size.width,
+                height: layout.size.height
+            })
+        },
+
+        None => None
+    };
+
+    let child_origin = match frame {
+        Some(frame) => Point { x: frame.x, y: frame.y },
+        None => origin
+    };
+
+    let mut children = vec![];
+    for child in displayed_children(key, components)? {
+        collect_accessibility_nodes(child, child_origin, focused, components, layouts, &mut children)?;
     }
 
-    // remove node from backing tree
+    Ok(AccessibilityNode {
+        tag: instance.tag,
+        role: properties.role,
+        label: properties.label.or_else(|| text_content(instance)),
+        hint: properties.hint,
+        value: properties.value,
+        frame,
+        is_focusable: focus::tab_index(instance).is_some(),
+        is_focused: focused == Some(key),
+        children
+    })
+}
 
-    Ok(layout_nodes)
+/// Returns how `instance` describes itself to assistive technology, if it does. Anything that
+/// can take focus does, with the default properties if its `Component` has none of its own, so
+/// that everything reachable with Tab is exposed.
+fn accessibility_properties(instance: &Instance) -> Option<AccessibilityProperties> {
+    match instance.component.accessibility_properties() {
+        Some(properties) => Some(properties),
+        None if focus::tab_index(instance).is_some() => Some(AccessibilityProperties::default()),
+        None => None
+    }
 }
 
-/// Given a tree, will walk the branches until it finds the next root nodes to connect.
-/// While this sounds slow, in practice it rarely has to go far in any direction. This could 
-/// potentially be done away with some hoisting magic in the `mount()` recursion, but I couldn't 
-/// find a pattern that didn't feel like some utter magic in Rust.
-///
-/// It might be because I'm writing this at 3AM. Feel free to improve it.
-fn link_layout_nodess(
-    parent: ComponentKey,
-    child: ComponentKey,
-    components: &mut ComponentStore,
-    layouts: &mut LayoutStore
+/// Adds the accessibility node for `key` to `nodes`, or, if `key` doesn't describe itself, the
+/// nodes for its children. Hidden (`Display::None`) subtrees are skipped.
+fn collect_accessibility_nodes(
+    key: ComponentKey,
+    origin: Point<f32>,
+    focused: Option<ComponentKey>,
+    components: &ComponentStore,
+    layouts: &LayoutStore,
+    nodes: &mut Vec<AccessibilityNode>
 ) -> Result<(), Box<Error>> {
-    if let (Ok(parent_instance), Ok(child_instance)) = (components.get(parent), components.get(child)) {
-        if let (Some(parent_layout), Some(child_layout)) = (parent_instance.layout, child_instance.layout) {
-            layouts.add_child(parent_layout, child_layout)?;
-
-            if let Some(platform_node) = child_instance.component.borrow_native_backing_node() {
-                parent_instance.component.append_child_node(platform_node);
-            }
+    let instance = components.get(key)?;
 
+    if let Some(layout) = instance.layout {
+        if layouts.style(layout)?.display == Display::None {
             return Ok(());
         }
     }
 
-    let children = components.children(child)?;
-    for child_key in children {
-        link_layout_nodess(parent, child_key, components, layouts)?;
+    match accessibility_properties(instance) {
+        Some(properties) => {
+            nodes.push(accessibility_node(key, properties, origin, focused, components, layouts)?);
+        },
+
+        // The children still sit within this node, if it's natively backed.
+        None => {
+            let origin = match instance.layout {
+                Some(layout) => {
+                    let location = layouts.layout(layout)?.location;
+                    Point { x: origin.x + location.x, y: origin.y + location.y }
+                },
+
+                None => origin
+            };
+
+            for child in displayed_children(key, components)? {
+                collect_accessibility_nodes(child, origin, focused, components, layouts, nodes)?;
+            }
+        }
     }
 
     Ok(())
 }
 
-/// Walks the tree and passes necessary Layout and Appearance-based styles to Components so they can
-/// update their backing widgets accordingly. This happens after a layout computation, typically.
-fn walk_and_apply_styles(
+/// Returns the event handlers for every `Component` from the root down to `key`.
+fn event_handlers_along_path(
     key: ComponentKey,
-    components: &mut ComponentStore,
-    layouts: &mut LayoutStore
-) -> Result<(), Box<Error>> {
-    let instance = components.get_mut(key)?;
+    components: &ComponentStore
+) -> Result<Vec<Arc<EventHandlers>>, Box<Error>> {
+    let mut path = vec![];
+    let mut current = Some(key);
 
-    if let Some(layout_key) = instance.layout {
-        instance.component.apply_styles(
-            &instance.appearance,
-            layouts.layout(layout_key)?
-        );
+    while let Some(key) = current {
+        path.push(components.get(key)?.event_handlers.clone());
+        current = components.parent(key)?;
     }
 
-    for child in components.children(key)? {
-        walk_and_apply_styles(child, components, layouts)?;
+    path.reverse();
+    Ok(path)
+}
+
+/// Returns the deepest `Component` at or beneath `key` that's under `point`, which is relative
+/// to the native node that `key` is laid out in.
+fn hit_test_component_tree(
+    key: ComponentKey,
+    point: Point<f32>,
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<Option<ComponentKey>, Box<Error>> {
+    let instance = components.get(key)?;
+
+    let (point, is_inside) = match instance.layout {
+        Some(layout_node) => {
+            let style = layouts.style(layout_node)?;
+            if style.display == Display::None {
+                return Ok(None);
+            }
+
+            let layout = layouts.layout(layout_node)?;
+            let point = Point { x: point.x - layout.location.x, y: point.y - layout.location.y };
+            let is_inside = point.x >= 0. && point.y >= 0.
+                && point.x < layout.size.width && point.y < layout.size.height;
+
+            if !is_inside && style.overflow == Overflow::Hidden {
+                return Ok(None);
+            }
+
+            (point, is_inside)
+        },
+
+        // Anything that isn't natively backed has no bounds of its own; it's only hit through
+        // its descendants.
+        None => (point, false)
+    };
+
+    for child in displayed_children(key, components)?.into_iter().rev() {
+        if let Some(hit) = hit_test_component_tree(child, point, components, layouts)? {
+            return Ok(Some(hit));
+        }
     }
 
-    Ok(())
+    Ok(if is_inside { Some(key) } else { None })
 }
@@ -0,0 +1,110 @@
+//! Implements `Patch`, the unit of work that rendering produces. Rendering happens in two
+//! phases: the render phase diffs the `Component` tree and computes layout, recording every
+//! change the backing tree needs as a `Patch`; the commit phase then applies them, in order,
+//! and only after that calls `component_did_mount()` and friends. Nothing native is touched
+//! until the commit phase.
+//!
+//! The `Component` tree itself does move on during the render phase: new `Component`s are
+//! mounted into it, and unmounted ones are taken out of it (though they're only dropped, and
+//! told through `component_will_unmount()`, once their `Unmount` is committed). The patches
+//! are what brings the backing tree along, so they always need committing - including when
+//! rendering fails part way, in which case whatever was recorded up to that point is committed
+//! before the error is returned.
+//!
+//! Patches refer to `Component`s by `ComponentKey`. A `Component` can be mounted and unmounted
+//! within the same render (e.g, by an error boundary resetting), so anything that refers to a
+//! key that's since gone is skipped when committing.
+
+use alchemy_styles::styles::{Appearance, Layout};
+
+use crate::accessibility::AccessibilityProperties;
+
+use crate::error::{Error, ErrorInfo};
+use crate::reconciler::key::ComponentKey;
+use crate::refs::Ref;
+use crate::traits::PlatformSpecificNodeType;
+
+/// A single change to the backing tree (or a lifecycle method to call, once the changes have
+/// been applied).
+#[derive(Debug)]
+pub enum Patch {
+    /// The `Component` at `key` was mounted; `component_did_mount()` is called for it.
+    Create(ComponentKey),
+
+    /// The `Component` at `key` was re-rendered; `component_did_update()` is called for it.
+    Update(ComponentKey),
+
+    /// The `Component` at `key` was unmounted; `component_will_unmount()` is called for it
+    /// (unless it never finished mounting), and it's dropped.
+    Unmount(ComponentKey),
+
+    /// Insert the native node for `child` at `index` among the native children of `parent`,
+    /// moving it if it's already there.
+    InsertAt {
+        parent: ComponentKey,
+        child: ComponentKey,
+        index: usize
+    },
+
+    /// Remove the native node for `child` from the native children of `parent`. This holds on
+    /// to the node itself, as `child` has usually been unmounted by the time this is applied.
+    Remove {
+        parent: ComponentKey,
+        child: ComponentKey,
+        node: PlatformSpecificNodeType
+    },
+
+    /// Swap the native node for `old` (held on to as `old_node`, for the same reason as
+    /// `Remove`) out for the native node of `new`, among the native children of `parent`.
+    Replace {
+        parent: ComponentKey,
+        old: ComponentKey,
+        old_node: PlatformSpecificNodeType,
+        new: ComponentKey
+    },
+
+    /// Hand the text passed as children to the `Component` at `key` (e.g, a `Text`) to it.
+    SetText {
+        key: ComponentKey,
+        text: String
+    },
+
+    /// Hand the accessibility properties of the `Component` at `key` to it, to forward to its
+    /// native node.
+    SetAccessibility {
+        key: ComponentKey,
+        properties: AccessibilityProperties
+    },
+
+    /// Point `node_ref` at the `Component` at `key`, once it's in place.
+    AttachRef {
+        key: ComponentKey,
+        node_ref: Ref
+    },
+
+    /// Clear `node_ref`, if it's still pointing at the `Component` at `key`.
+    DetachRef {
+        key: ComponentKey,
+        node_ref: Ref
+    },
+
+    /// Set the frame of the `Component` at `key` from a computed `Layout`.
+    SetFrame {
+        key: ComponentKey,
+        layout: Layout
+    },
+
+    /// Set the `Appearance` of the `Component` at `key`.
+    SetAppearance {
+        key: ComponentKey,
+        appearance: Appearance
+    },
+
+    /// The error boundary at `key` caught `error`, and mounted its fallback UI;
+    /// `component_did_catch()` is called for it.
+    Catch {
+        key: ComponentKey,
+        error: Error,
+        info: ErrorInfo
+    }
+}
@@ -4,8 +4,6 @@
 
 use std::collections::HashMap;
 
-pub use alchemy_styles::Appearance;
-
 use crate::reconciler::error::{RenderEngineError as Error};
 use crate::reconciler::instance::Instance;
 use crate::reconciler::key::{Allocator, Id, INSTANCE_ALLOCATOR, ComponentKey};
@@ -64,7 +62,9 @@ pub(crate) struct ComponentStore {
     nodes: Allocator,
     components: Storage<Instance>,
     parents: Storage<Vec<ComponentKey>>,
-    children: Storage<Vec<ComponentKey>>
+    children: Storage<Vec<ComponentKey>>,
+    portals: Vec<ComponentKey>,
+    unmounting: Storage<Instance>
 }
 
 impl ComponentStore {
@@ -74,10 +74,16 @@ impl ComponentStore {
             nodes: Allocator::new(),
             components: Storage::new(),
             parents: Storage::new(),
-            children: Storage::new()
+            children: Storage::new(),
+            portals: vec![],
+            unmounting: Storage::new()
         }
     }
 
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
     pub fn new_key(&mut self) -> ComponentKey {
         let local = self.nodes.allocate();
         ComponentKey { instance: self.id, local }
@@ -100,11 +106,29 @@ impl ComponentStore {
     }
 
     pub fn remove(&mut self, key: ComponentKey) -> Result<Instance, Error> {
-        self.parents.remove(key)?;
+        // Detach from any parents, so they don't end up pointing at a dead key.
+        for parent in self.parents.remove(key)? {
+            self.children.get_mut(parent)?.retain(|c| *c != key);
+        }
+
+        self.portals.retain(|portal| *portal != key);
         self.children.remove(key)?;
         self.components.remove(key)
     }
 
+    /// Takes `key` out of the tree, like `remove()`, but holds on to its instance until the
+    /// unmount is committed (see `take_unmounted()`), as it still needs to be told about it.
+    pub fn unmount(&mut self, key: ComponentKey) -> Result<(), Error> {
+        let instance = self.remove(key)?;
+        self.unmounting.insert(key, instance);
+        Ok(())
+    }
+
+    /// Lets go of the instance for `key`, once its unmount has been committed.
+    pub fn take_unmounted(&mut self, key: ComponentKey) -> Result<Instance, Error> {
+        self.unmounting.remove(key)
+    }
+
     pub fn add_child(&mut self, key: ComponentKey, child: ComponentKey) -> Result<(), Error> {
         self.parents.get_mut(child)?.push(key);
         self.children.get_mut(key)?.push(child);
@@ -148,12 +172,40 @@ impl ComponentStore {
         Ok(old_child)
     }
 
+    pub fn parent(&self, key: ComponentKey) -> Result<Option<ComponentKey>, Error> {
+        self.parents.get(key).map(|parents| parents.first().cloned())
+    }
+
     pub fn children(&self, key: ComponentKey) -> Result<Vec<ComponentKey>, Error> {
         self.children.get(key).map(Clone::clone)
     }
 
-    pub fn child_count(&self, key: ComponentKey) -> Result<usize, Error> {
-        self.children.get(key).map(Vec::len)
+    /// Registers `key` as a portal; see `portals_targeting()`.
+    pub fn add_portal(&mut self, key: ComponentKey) {
+        if !self.portals.contains(&key) {
+            self.portals.push(key);
+        }
+    }
+
+    pub fn remove_portal(&mut self, key: ComponentKey) {
+        self.portals.retain(|portal| *portal != key);
+    }
+
+    pub fn portals(&self) -> Vec<ComponentKey> {
+        self.portals.clone()
+    }
+
+    /// Returns the portals currently linking their children under `target`, in the order they
+    /// were registered.
+    pub fn portals_targeting(&self, target: ComponentKey) -> Result<Vec<ComponentKey>, Error> {
+        let mut portals = vec![];
+        for portal in &self.portals {
+            if self.components.get(*portal)?.portal_target == Some(target) {
+                portals.push(*portal);
+            }
+        }
+
+        Ok(portals)
     }
 
     pub fn get(&self, key: ComponentKey) -> Result<&Instance, Error> {
@@ -0,0 +1,382 @@
+//! Implements recording and replaying render sessions, for reproducing bugs (e.g, a layout
+//! glitch that only a customer can trigger). A `RenderEngine` that's recording logs every root
+//! render, resize, state update and input event it handles, along with the changes each commit
+//! made to the native tree (as remote `Operation`s), with timestamps:
+//!
+//! ```rust,ignore
+//! let render_engine = window.render_engine();
+//! render_engine.start_recording();
+//! // ...later, once the glitch has happened...
+//! render_engine.stop_recording().unwrap().save("glitch.json")?;
+//! ```
+//!
+//! The result is a versioned JSON file, suitable for attaching to an issue. Replaying it feeds
+//! the same inputs into a fresh engine, and reports the first commit that came out differently:
+//!
+//! ```rust,ignore
+//! let render_engine = RenderEngine::new();
+//! let root = render_engine.register_root_component(View::default())?;
+//! if let Some(divergence) = Recording::load("glitch.json")?.replay(&render_engine, root, || app.render())? {
+//!     println!("{}", divergence);
+//! }
+//! ```
+//!
+//! Nodes are identified relative to the engine that was recording (see `relative_node_id()`), so
+//! that they line up with the replaying engine's.
+//!
+//! State updates can't be replayed as-is (they're closures), so replays only use them to keep
+//! track of what's happened. Anything caused by a replayed render or input happens again on its
+//! own. Of the rest (e.g, an update on the back of a network response), forced updates are
+//! replayed through `RenderEngine::force_update()`; other updates aren't replayed at all, and
+//! the commits that follow them aren't compared, up until the next render, resize or input.
+//! Their effects can still show up as a divergence in later commits.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::Event;
+use crate::reconciler::RenderEngine;
+use crate::reconciler::error::RenderEngineError;
+use crate::reconciler::key::{ComponentKey, Id};
+use crate::remote;
+use crate::remote::{NodeId, Operation};
+use crate::rsx::RSX;
+
+/// The version of the recording format written by this release. Bumped whenever it changes in
+/// a way that older releases can't read.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Something a `RenderEngine` did while recording.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// The root was rendered (e.g, by `Window::render()`) at the given size.
+    Render { width: f64, height: f64 },
+
+    /// The root was resized, without being re-rendered.
+    Resize { width: f64, height: f64 },
+
+    /// A state update was queued for `target` (or, if `forced`, a re-render).
+    StateUpdate { target: NodeId, forced: bool },
+
+    /// `event` was dispatched to `target`.
+    Input { target: NodeId, event: Event },
+
+    /// A commit was made, with these changes to the native tree.
+    Commit { operations: Vec<Operation> }
+}
+
+/// A single, timestamped `Action`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When this happened, in milliseconds since recording started.
+    pub at: u64,
+
+    #[serde(flatten)]
+    pub action: Action
+}
+
+/// A recorded render session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// The version of the format this was written in (see `FORMAT_VERSION`).
+    pub version: u32,
+
+    /// When recording started, in milliseconds since the Unix epoch.
+    pub started_at: u64,
+
+    /// Everything that happened, in order.
+    pub entries: Vec<Entry>
+}
+
+impl Recording {
+    /// Returns this recording as (pretty-printed) JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Reads a recording from JSON, as written by `to_json()`.
+    pub fn from_json(json: &str) -> Result<Recording, Box<Error>> {
+        let recording: Recording = serde_json::from_str(json)?;
+
+        if recording.version > FORMAT_VERSION {
+            return Err(Box::new(RenderEngineError::UnsupportedRecordingVersion(recording.version)));
+        }
+
+        Ok(recording)
+    }
+
+    /// Writes this recording to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<Error>> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Reads a recording from the file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recording, Box<Error>> {
+        Recording::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Feeds the recorded inputs into `engine`, rendering the root at `root` with whatever
+    /// `render` returns, and compares each commit against the recorded one. Returns the first
+    /// `Divergence`, if there is one.
+    ///
+    /// `engine` should be fresh, with `root` registered first (as a `Window` does), so that
+    /// everything is keyed the same way it was when recording. Replaying watches commits (see
+    /// `RenderEngine::on_commit()`) and records state updates (to tell which of the recorded
+    /// ones it's reproduced) on `engine`, so any listener or recording in progress there is set
+    /// aside while it runs, and put back afterwards.
+    pub fn replay<F: FnMut() -> RSX>(
+        &self,
+        engine: &RenderEngine,
+        root: ComponentKey,
+        render: F
+    ) -> Result<Option<Divergence>, Box<Error>> {
+        let commits = Arc::new(Mutex::new(vec![]));
+        let listener_commits = commits.clone();
+        let engine_id = engine.id();
+        let listener = engine.replace_commit_listener(Some(Box::new(move |operations: &[Operation]| {
+            listener_commits.lock().unwrap().push(relative_operations(engine_id, operations));
+        })));
+
+        let recorder = engine.replace_recorder(Some(Recorder::new()));
+        let divergence = self.replay_entries(engine, root, render, &commits);
+        engine.replace_recorder(recorder);
+        engine.replace_commit_listener(listener);
+        divergence
+    }
+
+    /// Does the work for `replay()`, comparing against the commits that `engine` reports to
+    /// `commits` - or, for any that refer to another engine's nodes, the error.
+    fn replay_entries<F: FnMut() -> RSX>(
+        &self,
+        engine: &RenderEngine,
+        root: ComponentKey,
+        mut render: F,
+        commits: &Mutex<Vec<Result<Vec<Operation>, RenderEngineError>>>
+    ) -> Result<Option<Divergence>, Box<Error>> {
+        let mut next_commit = 0;
+        let mut reproduced_updates = vec![];
+        let mut skipping_commits = false;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            match &entry.action {
+                Action::Render { width, height } => {
+                    skipping_commits = false;
+                    engine.diff_and_render_root(root, (*width, *height), render())?;
+                },
+
+                Action::Resize { width, height } => {
+                    skipping_commits = false;
+                    engine.resize_root(root, (*width, *height))?;
+                },
+
+                Action::Input { target, event } => {
+                    skipping_commits = false;
+                    engine.dispatch_event_to(engine.key_for_relative_node_id(*target)?, event.clone())?;
+                },
+
+                Action::StateUpdate { target, forced } => {
+                    reproduced_updates.extend(engine.take_recorded_state_updates());
+
+                    if let Some(reproduced) = reproduced_updates.iter().position(|update| *update == (*target, *forced)) {
+                        reproduced_updates.remove(reproduced);
+                    } else if *forced {
+                        engine.force_update(engine.key_for_relative_node_id(*target)?)?;
+
+                        // Leave out the update we just made, but not anything it led to.
+                        let mut updates = engine.take_recorded_state_updates();
+                        if let Some(ours) = updates.iter().position(|update| *update == (*target, true)) {
+                            updates.remove(ours);
+                        }
+
+                        reproduced_updates.extend(updates);
+                    } else {
+                        skipping_commits = true;
+                    }
+                },
+
+                Action::Commit { .. } if skipping_commits => {},
+
+                Action::Commit { operations } => {
+                    let actual = replayed_commit(commits, next_commit)?;
+                    next_commit += 1;
+
+                    if let Some(divergence) = Divergence::between(index, entry.at, Some(operations), actual.as_ref()) {
+                        return Ok(Some(divergence));
+                    }
+                }
+            }
+        }
+
+        // Anything left over wasn't in the recording at all.
+        let actual = replayed_commit(commits, next_commit)?;
+        let at = self.entries.last().map(|entry| entry.at).unwrap_or(0);
+        Ok(Divergence::between(self.entries.len(), at, None, actual.as_ref()))
+    }
+}
+
+/// Returns the commit at `index` among those a replay has made so far, if it's made that many.
+fn replayed_commit(
+    commits: &Mutex<Vec<Result<Vec<Operation>, RenderEngineError>>>,
+    index: usize
+) -> Result<Option<Vec<Operation>>, Box<Error>> {
+    match commits.lock().unwrap().get(index).cloned() {
+        Some(Ok(operations)) => Ok(Some(operations)),
+        Some(Err(e)) => Err(Box::new(e)),
+        None => Ok(None)
+    }
+}
+
+/// Returns the `NodeId` for `key`, relative to `engine` - which is just the key, since engines
+/// key their `Component`s the same way given the same inputs. Keys from any other engine are
+/// rejected, as they'd be indistinguishable from `engine`'s own once made relative.
+pub(crate) fn relative_node_id(engine: Id, key: ComponentKey) -> Result<NodeId, RenderEngineError> {
+    if key.instance != engine {
+        return Err(RenderEngineError::InvalidComponentKey(key));
+    }
+
+    Ok(NodeId::from(key.local.value()))
+}
+
+/// Makes the `NodeId`s in `operations` relative to `engine`, which made them (see
+/// `relative_node_id()`). Roots are left out, as replays render into a root of their own. An id
+/// that isn't one of `engine`'s is rejected.
+pub(crate) fn relative_operations(engine: Id, operations: &[Operation]) -> Result<Vec<Operation>, RenderEngineError> {
+    let relative = |id: &NodeId| {
+        remote::key_for_node_id(*id)
+            .and_then(|key| relative_node_id(engine, key))
+            .map_err(|_| RenderEngineError::InvalidNodeId(*id))
+    };
+
+    let mut relative_operations = vec![];
+    for operation in operations {
+        relative_operations.push(match operation {
+            Operation::Root { .. } => { continue; },
+            Operation::Create { id, kind } => Operation::Create { id: relative(id)?, kind: kind.clone() },
+            Operation::Insert { parent, child, index } => Operation::Insert { parent: relative(parent)?, child: relative(child)?, index: *index },
+            Operation::Remove { parent, child } => Operation::Remove { parent: relative(parent)?, child: relative(child)? },
+            Operation::Replace { parent, old, new } => Operation::Replace { parent: relative(parent)?, old: relative(old)?, new: relative(new)? },
+            Operation::Destroy { id } => Operation::Destroy { id: relative(id)? },
+            Operation::SetFrame { id, layout } => Operation::SetFrame { id: relative(id)?, layout: *layout },
+            Operation::SetAppearance { id, appearance } => Operation::SetAppearance { id: relative(id)?, appearance: *appearance },
+            Operation::SetText { id, text } => Operation::SetText { id: relative(id)?, text: text.clone() }
+        });
+    }
+
+    Ok(relative_operations)
+}
+
+/// Where a replay first came out differently from its recording.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The index of the recorded commit that differed (or the number of entries, if the replay
+    /// made a commit that wasn't recorded).
+    pub entry: usize,
+
+    /// When the recorded commit happened (or the recording ended), in milliseconds since
+    /// recording started.
+    pub at: u64,
+
+    /// The index of the first operation that differed, within the commit.
+    pub operation: usize,
+
+    /// The commit that was recorded, if there was one.
+    pub expected: Option<Vec<Operation>>,
+
+    /// The commit that the replay made, if it made one.
+    pub actual: Option<Vec<Operation>>
+}
+
+impl Divergence {
+    /// Compares a recorded commit against a replayed one, returning where they first differ.
+    fn between(
+        entry: usize,
+        at: u64,
+        expected: Option<&Vec<Operation>>,
+        actual: Option<&Vec<Operation>>
+    ) -> Option<Divergence> {
+        let operation = match (expected, actual) {
+            (Some(expected), Some(actual)) => {
+                let length = expected.len().max(actual.len());
+                (0..length).find(|i| expected.get(*i) != actual.get(*i))?
+            },
+
+            (None, None) => { return None; },
+            _ => 0
+        };
+
+        Some(Divergence {
+            entry,
+            at,
+            operation,
+            expected: expected.cloned(),
+            actual: actual.cloned()
+        })
+    }
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "replay diverged at entry {} ({}ms): ", self.entry, self.at)?;
+
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => write!(
+                f,
+                "operation {} was {:?}, expected {:?}",
+                self.operation, actual.get(self.operation), expected.get(self.operation)
+            ),
+
+            (Some(_), None) => write!(f, "a commit was expected, but none was made"),
+            (None, _) => write!(f, "a commit was made that wasn't recorded")
+        }
+    }
+}
+
+/// Collects a `Recording` as it happens. `RenderEngine`s hold one of these while recording.
+pub(crate) struct Recorder {
+    started: Instant,
+    recording: Recording
+}
+
+impl Recorder {
+    /// Starts a new, empty recording.
+    pub(crate) fn new() -> Recorder {
+        let started_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_millis() as u64).unwrap_or(0);
+
+        Recorder {
+            started: Instant::now(),
+            recording: Recording {
+                version: FORMAT_VERSION,
+                started_at,
+                entries: vec![]
+            }
+        }
+    }
+
+    /// Logs `action`, timestamped as of now.
+    pub(crate) fn record(&mut self, action: Action) {
+        let at = self.started.elapsed().as_millis() as u64;
+        self.recording.entries.push(Entry { at, action });
+    }
+
+    /// Takes the `StateUpdate`s logged so far, as `(target, forced)` pairs, discarding
+    /// everything else.
+    pub(crate) fn take_state_updates(&mut self) -> Vec<(NodeId, bool)> {
+        self.recording.entries.drain(..).filter_map(|entry| match entry.action {
+            Action::StateUpdate { target, forced } => Some((target, forced)),
+            _ => None
+        }).collect()
+    }
+
+    /// Finishes recording, and returns the result.
+    pub(crate) fn finish(self) -> Recording {
+        self.recording
+    }
+}
@@ -0,0 +1,77 @@
+//! Implements `Ref`, which is how application code gets a handle to a mounted `Component` (e.g,
+//! to focus a text field, or read a view's computed frame) after an `rsx! {}` tree has been
+//! handed off to the reconciler.
+//!
+//! Pass one to a node with the `ref` attribute:
+//!
+//! ```rust,ignore
+//! <TextField ref={self.input} />
+//! ```
+//!
+//! Once that node has mounted, the `Ref` resolves to it; when it unmounts, the `Ref` is cleared.
+
+use std::sync::{Arc, Mutex};
+
+use alchemy_styles::styles::Layout;
+
+use crate::RENDER_ENGINE;
+use crate::reconciler::key::ComponentKey;
+use crate::traits::{Component, PlatformSpecificNodeType};
+
+/// A handle to a mounted `Component`. This is cheap to clone, and clones point at the same
+/// `Component` (and compare equal).
+///
+/// The accessors here go through the `RenderEngine`, which is locked while rendering - so don't
+/// call them from within a lifecycle method.
+#[derive(Clone, Debug, Default)]
+pub struct Ref(Arc<Mutex<Option<ComponentKey>>>);
+
+impl PartialEq for Ref {
+    fn eq(&self, other: &Ref) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Ref {
+    /// Creates a new, unattached `Ref`.
+    pub fn new() -> Ref {
+        Ref::default()
+    }
+
+    /// Returns the `ComponentKey` this `Ref` is attached to, if the node is mounted.
+    pub fn key(&self) -> Option<ComponentKey> {
+        *self.0.lock().unwrap()
+    }
+
+    /// Calls `handler` with the mounted `Component`, returning the result. Use `as_any()` to get
+    /// back to the concrete type. Returns `None` if the node isn't mounted.
+    pub fn with_component<R, F: FnOnce(&Component) -> R>(&self, handler: F) -> Option<R> {
+        RENDER_ENGINE.with_component(self.key()?, handler).ok()
+    }
+
+    /// Returns the native node backing the mounted `Component`. Returns `None` if the node isn't
+    /// mounted, or isn't natively backed.
+    pub fn native_node(&self) -> Option<PlatformSpecificNodeType> {
+        RENDER_ENGINE.native_node_for(self.key()?).ok()?
+    }
+
+    /// Returns the last computed `Layout` for the mounted `Component`. Returns `None` if the node
+    /// isn't mounted, or isn't natively backed.
+    pub fn layout(&self) -> Option<Layout> {
+        RENDER_ENGINE.layout_for(self.key()?).ok()?
+    }
+
+    /// Attaches this `Ref` to `key`.
+    pub(crate) fn attach(&self, key: ComponentKey) {
+        *self.0.lock().unwrap() = Some(key);
+    }
+
+    /// Clears this `Ref`, if it's still attached to `key` (it may since have been handed to
+    /// another node).
+    pub(crate) fn detach(&self, key: ComponentKey) {
+        let mut attached = self.0.lock().unwrap();
+        if *attached == Some(key) {
+            *attached = None;
+        }
+    }
+}
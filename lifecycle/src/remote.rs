@@ -0,0 +1,573 @@
+//! Implements remote rendering: driving a UI whose `Component`s live in another process (e.g, a
+//! plugin host, or a long-running engine). The process that owns the `Component`s renders as
+//! usual, and streams every commit over a `Connection` as a list of `Operation`s - the changes
+//! to its native tree, in a serializable form. On the other end, a `Mirror` applies them to
+//! native nodes of its own, under a root of its choosing (e.g, a `Window`'s). Events reported
+//! against those nodes are sent back the other way, and dispatched as though they'd happened
+//! locally.
+//!
+//! ```rust,ignore
+//! // In the process that owns the `Component`s...
+//! let connection = Arc::new(Connection::stdio());
+//! remote::stream_commits(&render_engine, connection.clone());
+//! render_engine.diff_and_render_root(root, (800., 600.), tree)?;
+//! remote::dispatch_events(&render_engine, &connection)?;
+//!
+//! // ...and in the process that displays them.
+//! let mirror = alchemy::remote::mirror(window.render_key(), Arc::new(Connection::unix(path)?));
+//! mirror.run()?;
+//! ```
+//!
+//! Messages are newline-delimited JSON. Attach both ends before the first render, as a
+//! `Mirror` can only build on what it's been sent.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex, Weak};
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::Path;
+
+use alchemy_styles::lazy_static;
+use alchemy_styles::styles::{Appearance, Layout};
+use serde::{Deserialize, Serialize};
+
+use crate::events::{is_same_native_node, Event};
+use crate::reconciler::RenderEngine;
+use crate::reconciler::error::RenderEngineError;
+use crate::reconciler::key::{ComponentKey, Id};
+use crate::reconciler::patch::Patch;
+use crate::reconciler::storage::ComponentStore;
+use crate::traits::{Component, PlatformSpecificNodeType};
+
+/// Identifies a native node in a remote tree. The upper 32 bits are the `RenderEngine` that
+/// owns it, and the lower 32 bits are its `Component` within that engine; neither is ever 0.
+pub type NodeId = u64;
+
+/// A change to a native tree, as sent over the wire.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// The node `id` is a root (e.g, a `Window`'s), and isn't created like everything else: a
+    /// `Mirror` supplies its own. Sent in any commit that changes what's under it, ahead of
+    /// anything that refers to it.
+    Root { id: NodeId },
+
+    /// Create a new, detached node. `kind` is the tag of the `Component` it belongs to (e.g,
+    /// "View", "Text").
+    Create { id: NodeId, kind: String },
+
+    /// Insert `child` at `index` among the children of `parent`, moving it if it's already
+    /// attached.
+    Insert { parent: NodeId, child: NodeId, index: usize },
+
+    /// Remove `child` from the children of `parent`.
+    Remove { parent: NodeId, child: NodeId },
+
+    /// Swap `old` out for `new`, among the children of `parent`.
+    Replace { parent: NodeId, old: NodeId, new: NodeId },
+
+    /// Drop a node (which has already been removed), along with everything beneath it.
+    Destroy { id: NodeId },
+
+    /// Set the frame of a node.
+    SetFrame { id: NodeId, layout: Layout },
+
+    /// Set the `Appearance` of a node.
+    SetAppearance { id: NodeId, appearance: Appearance },
+
+    /// Set the text displayed by a node.
+    SetText { id: NodeId, text: String }
+}
+
+/// A message sent over a `Connection`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    /// The operations for a single commit, to be applied in order.
+    Commit(Vec<Operation>),
+
+    /// An event that was reported against the node `target`.
+    Event { target: NodeId, event: Event }
+}
+
+/// A two-way stream of `Message`s, over a Unix socket, stdio, or anything else that can be read
+/// from and written to. Sending and receiving are locked separately, so one thread can wait on
+/// messages while another sends them.
+pub struct Connection {
+    reader: Mutex<BufReader<Box<Read + Send>>>,
+    writer: Mutex<Box<Write + Send>>,
+    failed_commit: Mutex<Option<io::Error>>
+}
+
+impl Connection {
+    /// Creates a `Connection` that receives from `reader`, and sends to `writer`.
+    pub fn new(reader: Box<Read + Send>, writer: Box<Write + Send>) -> Connection {
+        Connection {
+            reader: Mutex::new(BufReader::new(reader)),
+            writer: Mutex::new(writer),
+            failed_commit: Mutex::new(None)
+        }
+    }
+
+    /// Creates a `Connection` over stdin and stdout, for a process that's been spawned by the
+    /// other end.
+    pub fn stdio() -> Connection {
+        Connection::new(Box::new(io::stdin()), Box::new(io::stdout()))
+    }
+
+    /// Creates a `Connection` over a connected Unix socket.
+    #[cfg(unix)]
+    pub fn from_unix_stream(stream: UnixStream) -> Result<Connection, Box<Error>> {
+        let reader = stream.try_clone()?;
+        Ok(Connection::new(Box::new(reader), Box::new(stream)))
+    }
+
+    /// Connects to the Unix socket at `path`.
+    #[cfg(unix)]
+    pub fn unix<P: AsRef<Path>>(path: P) -> Result<Connection, Box<Error>> {
+        Connection::from_unix_stream(UnixStream::connect(path)?)
+    }
+
+    /// Creates two `Connection`s, connected to each other. This is handy for running both ends in
+    /// one process (e.g, for testing).
+    #[cfg(unix)]
+    pub fn pair() -> Result<(Connection, Connection), Box<Error>> {
+        let (a, b) = UnixStream::pair()?;
+        Ok((Connection::from_unix_stream(a)?, Connection::from_unix_stream(b)?))
+    }
+
+    /// Sends `message`.
+    pub fn send(&self, message: &Message) -> Result<(), Box<Error>> {
+        let mut writer = self.writer.lock().unwrap();
+        serde_json::to_writer(&mut *writer, message)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Waits for the next message. Returns `None` once the other end hangs up.
+    pub fn receive(&self) -> Result<Option<Message>, Box<Error>> {
+        let mut line = String::new();
+        if self.reader.lock().unwrap().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&line)?))
+    }
+}
+
+/// Sends every commit on `engine` over `connection`, from here on out. A `Mirror` can only
+/// build on what it's been sent, so once a commit can't be sent, nothing more is; the error is
+/// returned from `dispatch_events()` on the same `connection`.
+pub fn stream_commits(engine: &RenderEngine, connection: Arc<Connection>) {
+    engine.on_commit(move |operations| {
+        let mut failed_commit = connection.failed_commit.lock().unwrap();
+        if failed_commit.is_some() {
+            return;
+        }
+
+        if let Err(e) = connection.send(&Message::Commit(operations.to_vec())) {
+            *failed_commit = Some(match e.downcast::<io::Error>() {
+                Ok(e) => *e,
+                Err(e) => io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+            });
+        }
+    });
+}
+
+/// Dispatches events received over `connection` on `engine` (see
+/// `RenderEngine::dispatch_event_to()`), until the other end hangs up. This blocks, so you'll
+/// generally want to run it on a thread of its own.
+///
+/// Events for nodes that aren't in `engine` (e.g, that were removed while the event was on its
+/// way) are dropped. Anything else that goes wrong - dispatching, or sending a commit (see
+/// `stream_commits()`) - ends this, returning the error.
+pub fn dispatch_events(engine: &RenderEngine, connection: &Connection) -> Result<(), Box<Error>> {
+    while let Some(message) = connection.receive()? {
+        check_commits_sent(connection)?;
+
+        match message {
+            Message::Event { target, event } => {
+                let dispatched = engine.key_for_node_id(target).and_then(|key| engine.dispatch_event_to(key, event));
+
+                if let Err(e) = dispatched {
+                    match e.downcast_ref::<RenderEngineError>() {
+                        Some(RenderEngineError::InvalidNodeId(_)) | Some(RenderEngineError::InvalidComponentKey(_)) => {},
+                        _ => { return Err(e); }
+                    }
+                }
+            },
+
+            Message::Commit(_) => { return Err(unexpected_message("a commit, from a mirror")); }
+        }
+    }
+
+    check_commits_sent(connection)
+}
+
+/// Returns the error that stopped `stream_commits()` sending over `connection`, if it has.
+fn check_commits_sent(connection: &Connection) -> Result<(), Box<Error>> {
+    match connection.failed_commit.lock().unwrap().take() {
+        Some(e) => Err(Box::new(e)),
+        None => Ok(())
+    }
+}
+
+/// The error for receiving a message that's meant for the other end (`description`).
+fn unexpected_message(description: &str) -> Box<Error> {
+    Box::new(io::Error::new(io::ErrorKind::InvalidData, format!("Received {}, which only goes the other way", description)))
+}
+
+/// Returns the `NodeId` for `key`.
+pub(crate) fn node_id(key: ComponentKey) -> NodeId {
+    NodeId::from(key.instance.value()) << 32 | NodeId::from(key.local.value())
+}
+
+/// Returns the `ComponentKey` that `id` was made from (see `node_id()`), or an error if it
+/// couldn't have come from one.
+pub(crate) fn key_for_node_id(id: NodeId) -> Result<ComponentKey, RenderEngineError> {
+    let (instance, local) = ((id >> 32) as u32, id as u32);
+
+    if instance == 0 || local == 0 {
+        return Err(RenderEngineError::InvalidNodeId(id));
+    }
+
+    Ok(ComponentKey {
+        instance: Id::new(instance),
+        local: Id::new(local)
+    })
+}
+
+/// Translates `patches` into `Operation`s, ahead of them being committed. Only natively backed
+/// `Component`s have a node on the other end; everything else (including lifecycle methods) is
+/// left out.
+pub(crate) fn operations_for(patches: &[Patch], components: &ComponentStore) -> Vec<Operation> {
+    let is_native = |key: ComponentKey| match components.get(key) {
+        Ok(instance) => instance.layout.is_some(),
+        Err(_) => false
+    };
+
+    let is_root = |key: ComponentKey| match components.parent(key) {
+        Ok(parent) => parent.is_none(),
+        Err(_) => false
+    };
+
+    // Patches refer to nodes before they're reported as mounted (which happens once their
+    // children are in place), so creating them all up front keeps things simple.
+    let mut operations: Vec<Operation> = patches.iter().filter_map(|patch| match patch {
+        Patch::Create(key) if is_native(*key) => Some(Operation::Create {
+            id: node_id(*key),
+            kind: components.get(*key).unwrap().tag.to_string()
+        }),
+
+        _ => None
+    }).collect();
+
+    let mut roots = vec![];
+    for patch in patches {
+        if let Patch::InsertAt { parent, .. } | Patch::Remove { parent, .. } | Patch::Replace { parent, .. } = patch {
+            if is_root(*parent) && !roots.contains(parent) {
+                roots.push(*parent);
+                operations.push(Operation::Root { id: node_id(*parent) });
+            }
+        }
+
+        match patch {
+            Patch::InsertAt { parent, child, index } => operations.push(Operation::Insert {
+                parent: node_id(*parent),
+                child: node_id(*child),
+                index: *index
+            }),
+
+            // Anything that's been removed and isn't around anymore has been unmounted.
+            Patch::Remove { parent, child, .. } => {
+                let child_id = node_id(*child);
+                operations.push(Operation::Remove { parent: node_id(*parent), child: child_id });
+
+                if components.get(*child).is_err() {
+                    operations.push(Operation::Destroy { id: child_id });
+                }
+            },
+
+            Patch::Replace { parent, old, new, .. } => {
+                let old_id = node_id(*old);
+                operations.push(Operation::Replace {
+                    parent: node_id(*parent),
+                    old: old_id,
+                    new: node_id(*new)
+                });
+
+                if components.get(*old).is_err() {
+                    operations.push(Operation::Destroy { id: old_id });
+                }
+            },
+
+            // The other end supplies its own root, and sizes it as it sees fit.
+            Patch::SetText { key, .. } | Patch::SetFrame { key, .. } | Patch::SetAppearance { key, .. }
+                if !is_native(*key) || is_root(*key) => {},
+
+            Patch::SetText { key, text } => operations.push(Operation::SetText {
+                id: node_id(*key),
+                text: text.clone()
+            }),
+
+            Patch::SetFrame { key, layout } => operations.push(Operation::SetFrame {
+                id: node_id(*key),
+                layout: *layout
+            }),
+
+            Patch::SetAppearance { key, appearance } => operations.push(Operation::SetAppearance {
+                id: node_id(*key),
+                appearance: *appearance
+            }),
+
+            // Accessibility properties aren't part of the wire format, so mirrors go without; the
+            // rest only matters on this side.
+            Patch::Create(_) | Patch::Update(_) | Patch::Unmount(_) | Patch::SetAccessibility { .. } |
+            Patch::AttachRef { .. } | Patch::DetachRef { .. } | Patch::Catch { .. } => {}
+        }
+    }
+
+    operations
+}
+
+/// Creates the `Component` that backs a node of the given kind, or `None` if it's not a kind
+/// the `Mirror` knows about.
+pub type ComponentFactory = Box<Fn(&str) -> Option<Box<Component>> + Send + Sync>;
+
+/// A node in a `Mirror`.
+struct MirroredNode {
+    component: Box<Component>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    layout: Option<Layout>,
+    appearance: Appearance
+}
+
+lazy_static! {
+    /// Every live `Mirror`, so that events reported against their nodes can find their way to
+    /// them.
+    static ref MIRRORS: Mutex<Vec<Weak<Mirror>>> = Mutex::new(vec![]);
+}
+
+/// Mirrors a remote tree under a local root, applying the `Operation`s received over a
+/// `Connection`. Nodes are backed by `Component`s, as created by a `ComponentFactory` (e.g,
+/// `alchemy::remote::mirror()` creates `View`s and `Text`s), though they're not mounted in a
+/// `RenderEngine`; the `Component`s themselves live elsewhere.
+///
+/// A `Mirror` stands in for a single remote root: the first one it's told about (see
+/// `Operation::Root`). Anything under another is skipped.
+pub struct Mirror {
+    root: ComponentKey,
+    remote_root: Mutex<Option<NodeId>>,
+    connection: Arc<Connection>,
+    create_component: ComponentFactory,
+    nodes: Mutex<HashMap<NodeId, MirroredNode>>
+}
+
+impl Mirror {
+    /// Creates a `Mirror` that attaches the remote tree under `root` (e.g, a `Window`'s root),
+    /// and sends events reported against its nodes over `connection`.
+    pub fn new(root: ComponentKey, connection: Arc<Connection>, create_component: ComponentFactory) -> Arc<Mirror> {
+        let mirror = Arc::new(Mirror {
+            root,
+            remote_root: Mutex::new(None),
+            connection,
+            create_component,
+            nodes: Mutex::new(HashMap::new())
+        });
+
+        let mut mirrors = MIRRORS.lock().unwrap();
+        mirrors.retain(|mirror| mirror.upgrade().is_some());
+        mirrors.push(Arc::downgrade(&mirror));
+        mirror
+    }
+
+    /// Applies commits received over the connection, until the other end hangs up. This blocks,
+    /// so you'll generally want to run it on a thread of its own.
+    pub fn run(&self) -> Result<(), Box<Error>> {
+        while let Some(message) = self.connection.receive()? {
+            match message {
+                Message::Commit(operations) => self.apply(&operations)?,
+                Message::Event { .. } => { return Err(unexpected_message("an event, from a remote tree")); }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies `operations`, in order. Anything that refers to a node this doesn't know about
+    /// (including those of a kind the `ComponentFactory` can't create, and those under another
+    /// remote root) is skipped.
+    pub fn apply(&self, operations: &[Operation]) -> Result<(), Box<Error>> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let mut remote_root = self.remote_root.lock().unwrap();
+
+        for operation in operations {
+            match operation {
+                Operation::Root { id } => {
+                    if remote_root.is_none() {
+                        *remote_root = Some(*id);
+                    }
+                },
+
+                Operation::Create { id, kind } => {
+                    if let Some(component) = (self.create_component)(kind) {
+                        nodes.insert(*id, MirroredNode {
+                            component,
+                            parent: None,
+                            children: vec![],
+                            layout: None,
+                            appearance: Appearance::default()
+                        });
+                    }
+                },
+
+                Operation::Insert { parent, child, index } => {
+                    let node = match nodes.get(child).and_then(|child| child.component.borrow_native_backing_node()) {
+                        Some(node) => node,
+                        None => { continue; }
+                    };
+
+                    if *remote_root != Some(*parent) && !nodes.contains_key(parent) {
+                        continue;
+                    }
+
+                    self.with_parent(&nodes, *remote_root, *parent, |parent, offset| {
+                        parent.insert_child_node_at(node, index + offset);
+                    })?;
+                    detach_mirrored(&mut nodes, *child);
+
+                    if let Some(parent_node) = nodes.get_mut(parent) {
+                        let index = (*index).min(parent_node.children.len());
+                        parent_node.children.insert(index, *child);
+                    }
+
+                    nodes.get_mut(child).unwrap().parent = Some(*parent);
+                },
+
+                Operation::Remove { parent, child } => {
+                    if let Some(node) = nodes.get(child).and_then(|child| child.component.borrow_native_backing_node()) {
+                        self.with_parent(&nodes, *remote_root, *parent, |parent, _| parent.remove_child_node(node))?;
+                        detach_mirrored(&mut nodes, *child);
+                    }
+                },
+
+                Operation::Replace { parent, old, new } => {
+                    let old_node = nodes.get(old).and_then(|old| old.component.borrow_native_backing_node());
+                    let new_node = nodes.get(new).and_then(|new| new.component.borrow_native_backing_node());
+
+                    if let (Some(old_node), Some(new_node)) = (old_node, new_node) {
+                        self.with_parent(&nodes, *remote_root, *parent, |parent, _| parent.replace_child_node(old_node, new_node))?;
+                        detach_mirrored(&mut nodes, *new);
+
+                        if let Some(parent_node) = nodes.get_mut(parent) {
+                            if let Some(index) = parent_node.children.iter().position(|child| child == old) {
+                                parent_node.children[index] = *new;
+                            }
+                        }
+
+                        nodes.get_mut(new).unwrap().parent = Some(*parent);
+                        nodes.get_mut(old).unwrap().parent = None;
+                    }
+                },
+
+                Operation::Destroy { id } => { destroy_mirrored(&mut nodes, *id); },
+
+                Operation::SetFrame { id, layout } => {
+                    if let Some(node) = nodes.get_mut(id) {
+                        node.layout = Some(*layout);
+                        node.component.apply_styles(&node.appearance, layout);
+                    }
+                },
+
+                Operation::SetAppearance { id, appearance } => {
+                    if let Some(node) = nodes.get_mut(id) {
+                        node.appearance = *appearance;
+
+                        // Until there's a frame, there's nothing to apply this with; it's picked
+                        // up when there is.
+                        if let Some(layout) = node.layout {
+                            node.component.apply_styles(appearance, &layout);
+                        }
+                    }
+                },
+
+                Operation::SetText { id, text } => {
+                    if let Some(node) = nodes.get(id) {
+                        node.component.apply_text(text);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `handler` with the `Component` for `parent` - which, for the remote root, is the
+    /// local root - and where its mirrored children start among its native ones. The local
+    /// root's own native children come first (that's where its `RenderEngine` keeps them), so
+    /// anything mirrored under it goes after them; remote indices are offset to match.
+    fn with_parent<F: FnOnce(&Component, usize)>(
+        &self,
+        nodes: &HashMap<NodeId, MirroredNode>,
+        remote_root: Option<NodeId>,
+        parent: NodeId,
+        handler: F
+    ) -> Result<(), Box<Error>> {
+        if remote_root == Some(parent) {
+            if let Some(engine) = RenderEngine::for_key(self.root) {
+                engine.with_native_parent(self.root, handler)?;
+            }
+        } else if let Some(parent) = nodes.get(&parent) {
+            handler(&*parent.component, 0);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `NodeId` for the native `node`, if it's one of ours.
+    fn node_id_for(&self, node: &PlatformSpecificNodeType) -> Option<NodeId> {
+        let nodes = self.nodes.lock().unwrap();
+        nodes.iter().find(|(_, mirrored)| match mirrored.component.borrow_native_backing_node() {
+            Some(mirrored) => is_same_native_node(&mirrored, node),
+            None => false
+        }).map(|(id, _)| *id)
+    }
+}
+
+/// Unlinks `id` from its parent, as tracked by the `Mirror`.
+fn detach_mirrored(nodes: &mut HashMap<NodeId, MirroredNode>, id: NodeId) {
+    let parent = nodes.get_mut(&id).and_then(|node| node.parent.take());
+
+    if let Some(parent) = parent.and_then(|parent| nodes.get_mut(&parent)) {
+        parent.children.retain(|child| *child != id);
+    }
+}
+
+/// Drops `id` and everything beneath it.
+fn destroy_mirrored(nodes: &mut HashMap<NodeId, MirroredNode>, id: NodeId) {
+    detach_mirrored(nodes, id);
+
+    if let Some(node) = nodes.remove(&id) {
+        for child in node.children {
+            destroy_mirrored(nodes, child);
+        }
+    }
+}
+
+/// If `node` belongs to a `Mirror`, sends `event` back over its connection.
+pub(crate) fn dispatch_to_mirrors(node: &PlatformSpecificNodeType, event: Event) -> Result<(), Box<Error>> {
+    let mirrors: Vec<Arc<Mirror>> = MIRRORS.lock().unwrap().iter().filter_map(Weak::upgrade).collect();
+
+    for mirror in mirrors {
+        if let Some(target) = mirror.node_id_for(node) {
+            return mirror.connection.send(&Message::Event { target, event });
+        }
+    }
+
+    Ok(())
+}
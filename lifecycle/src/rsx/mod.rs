@@ -5,6 +5,7 @@
 
 use std::any::Any;
 use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use alchemy_styles::StylesList;
 
@@ -14,11 +15,14 @@ pub use virtual_node::VirtualNode;
 mod virtual_text;
 pub use virtual_text::VirtualText;
 
+use crate::events::{EventHandlers, KeyboardEvent, MouseEvent};
 use crate::reconciler::key::ComponentKey;
+use crate::refs::Ref;
 use crate::traits::Component;
 
 /// An enum representing the types of nodes that the
 /// system can work with. `None`, `VirtualText`, or `VirtualNode`.
+#[derive(Clone)]
 pub enum RSX {
     None,
     VirtualText(VirtualText),
@@ -28,7 +32,7 @@ pub enum RSX {
 impl RSX {
     /// Shorthand method for creating a new `RSX::VirtualNode` instance. Rarely should you call
     /// this yourself; the `rsx! {}` macro handles this for you.
-    pub fn node<P: Any + 'static>(
+    pub fn node<P: Any + Send + Sync + 'static>(
         tag: &'static str,
         styles: StylesList,
         create_fn: fn(key: ComponentKey) -> Box<Component>,
@@ -37,13 +41,148 @@ impl RSX {
     ) -> RSX {
         RSX::VirtualNode(VirtualNode {
             tag: tag,
+            key: None,
+            node_ref: None,
+            tab_index: None,
+            event_handlers: Arc::new(EventHandlers::default()),
             create_component_fn: create_fn,
             styles: styles,
-            props: Box::new(props),
+            props: Arc::new(props),
             children: children
         })
     }
     
+    /// Sets the key for this node, which the reconciler uses to match it against its previous
+    /// render among its siblings. Rarely should you call this yourself; the `rsx! {}` macro
+    /// handles this for you when you pass a `key` attribute. This is a no-op for anything other
+    /// than a `VirtualNode`.
+    pub fn with_key<K: ToString>(mut self, key: K) -> RSX {
+        if let RSX::VirtualNode(node) = &mut self {
+            node.key = Some(key.to_string());
+        }
+
+        self
+    }
+
+    /// Sets the `Ref` for this node, which the reconciler attaches to the `Component` once it's
+    /// mounted. Rarely should you call this yourself; the `rsx! {}` macro handles this for you
+    /// when you pass a `ref` attribute. This is a no-op for anything other than a `VirtualNode`.
+    pub fn with_ref(mut self, node_ref: &Ref) -> RSX {
+        if let RSX::VirtualNode(node) = &mut self {
+            node.node_ref = Some(node_ref.clone());
+        }
+
+        self
+    }
+
+    /// Overrides whether this node takes keyboard focus, much like `tabindex` in HTML: any value
+    /// makes it focusable, with `0` placing it in the Tab order by its position in the tree,
+    /// positive values placing it ahead of those (lowest first), and negative values leaving it
+    /// out of the Tab order entirely (it can still be clicked or focused programmatically).
+    /// Rarely should you call this yourself; the `rsx! {}` macro handles this for you when you
+    /// pass a `tabIndex` attribute. This is a no-op for anything other than a `VirtualNode`.
+    pub fn with_tab_index(mut self, tab_index: i32) -> RSX {
+        if let RSX::VirtualNode(node) = &mut self {
+            node.tab_index = Some(tab_index);
+        }
+
+        self
+    }
+
+    /// Sets the handler for clicks on this node. Rarely should you call this yourself; the
+    /// `rsx! {}` macro handles this for you when you pass an `onClick` attribute (and likewise
+    /// for the other `on_*` methods). These are all no-ops for anything other than a
+    /// `VirtualNode`.
+    pub fn on_click<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_click = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for clicks on this node, which runs on the way down to
+    /// the target rather than back up from it. The `rsx! {}` macro calls this (and the other
+    /// `on_*_capture` methods) for `onClickCapture` and the like.
+    pub fn on_click_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_click = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for mouse downs on this node.
+    pub fn on_mouse_down<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_mouse_down = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for mouse downs on this node.
+    pub fn on_mouse_down_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_mouse_down = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for mouse ups on this node.
+    pub fn on_mouse_up<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_mouse_up = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for mouse ups on this node.
+    pub fn on_mouse_up_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_mouse_up = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for the mouse moving over this node.
+    pub fn on_mouse_move<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_mouse_move = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for the mouse moving over this node.
+    pub fn on_mouse_move_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_mouse_move = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for the mouse entering this node.
+    pub fn on_mouse_enter<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_mouse_enter = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for the mouse entering this node.
+    pub fn on_mouse_enter_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_mouse_enter = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for the mouse leaving this node.
+    pub fn on_mouse_leave<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_mouse_leave = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for the mouse leaving this node.
+    pub fn on_mouse_leave_capture<F: Fn(&MouseEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_mouse_leave = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for key downs on this node.
+    pub fn on_key_down<F: Fn(&KeyboardEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_key_down = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for key downs on this node.
+    pub fn on_key_down_capture<F: Fn(&KeyboardEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_key_down = Some(Arc::new(handler)))
+    }
+
+    /// Sets the handler for key ups on this node.
+    pub fn on_key_up<F: Fn(&KeyboardEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.bubble.on_key_up = Some(Arc::new(handler)))
+    }
+
+    /// Sets the capture phase handler for key ups on this node.
+    pub fn on_key_up_capture<F: Fn(&KeyboardEvent) + Send + Sync + 'static>(self, handler: F) -> RSX {
+        self.with_event_handlers(|handlers| handlers.capture.on_key_up = Some(Arc::new(handler)))
+    }
+
+    /// Calls `handler` with the `EventHandlers` for this node, if it's a `VirtualNode`.
+    fn with_event_handlers<F: FnOnce(&mut EventHandlers)>(mut self, handler: F) -> RSX {
+        if let RSX::VirtualNode(node) = &mut self {
+            handler(Arc::make_mut(&mut node.event_handlers));
+        }
+
+        self
+    }
+
     /// Shorthand method for creating a new `RSX::VirtualText` instance. Rarely should you call
     /// this yourself; the `rsx! {}` and `text!()` macros handle this for you. 
     pub fn text(s: String) -> RSX {
@@ -3,19 +3,38 @@
 
 use std::any::Any;
 use std::fmt::{Display, Debug};
+use std::sync::Arc;
 
 use alchemy_styles::StylesList;
 
+use crate::events::EventHandlers;
 use crate::reconciler::key::ComponentKey;
+use crate::refs::Ref;
 use crate::rsx::RSX;
 use crate::traits::Component;
 
 /// A VirtualNode is akin to an `Element` in React terms. Here, we provide a way
 /// for lazy `Component` instantiation, properties, children and so on.
+#[derive(Clone)]
 pub struct VirtualNode {
     /// Used in debugging/printing/etc.
     pub tag: &'static str,
 
+    /// An optional key, used by the reconciler to match this node against its previous
+    /// render when its position among its siblings changes (e.g, items in a list).
+    pub key: Option<String>,
+
+    /// An optional `Ref`, which the reconciler attaches to the `Component` once it's mounted.
+    pub node_ref: Option<Ref>,
+
+    /// An optional override for whether (and in what order) this node takes keyboard focus;
+    /// see `RSX::with_tab_index()`.
+    pub tab_index: Option<i32>,
+
+    /// Handlers for events on this node, which the reconciler stores with the `Component`.
+    /// These are reference counted for the same reason `props` are.
+    pub event_handlers: Arc<EventHandlers>,
+
     /// Used for determining which CSS styles should be applied to this node.
     /// This property is accessed often enough that it's separated out here.
     pub styles: StylesList,
@@ -27,7 +46,10 @@ pub struct VirtualNode {
     /// When some RSX is returned, we scoop up the props inside a special block, and then shove
     /// them in here as an `Any` object. When you `derive(Props)` on a `Component` struct, it 
     /// creates a setter that specifically handles downcasting and persisting props for you.
-    pub props: Box<Any>,
+    ///
+    /// These are reference counted, so that nodes can be cheaply cloned and held onto for
+    /// re-rendering later.
+    pub props: Arc<Any + Send + Sync>,
 
     /// Child components for this node.
     pub children: Vec<RSX>
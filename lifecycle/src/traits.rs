@@ -2,11 +2,14 @@
 //! lifecycle, coupled with a delegate pattern inspired by those found in AppKit/UIKit.
 
 use std::any::Any;
+use std::sync::Arc;
 
-use alchemy_styles::styles::{Appearance, Layout};
+use alchemy_styles::styles::{Appearance, Layout, MeasureFunc};
 
-//use crate::RENDER_ENGINE;
-use crate::error::Error;
+use crate::RENDER_ENGINE;
+use crate::accessibility::AccessibilityProperties;
+use crate::context::Context;
+use crate::error::{Error, ErrorInfo};
 use crate::reconciler::key::ComponentKey;
 use crate::rsx::RSX;
 
@@ -15,13 +18,25 @@ use crate::rsx::RSX;
 pub type PlatformSpecificNodeType = objc_id::ShareId<objc::runtime::Object>;
 
 /// A per-platform wrapped Pointer type, used for attaching views/widgets.
-#[cfg(not(feature = "cocoa"))]
+#[cfg(all(feature = "headless", not(feature = "cocoa")))]
+pub type PlatformSpecificNodeType = crate::headless::NativeNode;
+
+/// A per-platform wrapped Pointer type, used for attaching views/widgets.
+#[cfg(not(any(feature = "cocoa", feature = "headless")))]
 pub type PlatformSpecificNodeType = ();
 
-/*fn update<C: Component, F: Fn() -> Box<C> + Send + Sync + 'static>(component: &Component, updater: F) {
-    let component_ptr = component as *const C as usize;
-    RENDER_ENGINE.queue_update_for(component_ptr, Box::new(updater));
-}*/
+/// Allows the reconciler to hand a `Component` trait object back to code that knows its
+/// concrete type (e.g, for queued state updates). This is implemented for you on every
+/// `'static` type, so you shouldn't ever need to implement it yourself.
+pub trait AsAny {
+    fn as_any(&self) -> &Any;
+    fn as_any_mut(&mut self) -> &mut Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &Any { self }
+    fn as_any_mut(&mut self) -> &mut Any { self }
+}
 
 /// Each platform tends to have their own startup routine, their own runloop, and so on.
 /// Alchemy recognizes this and provides an `AppDelegate` that receives events at a system
@@ -72,8 +87,20 @@ pub trait WindowDelegate: Send + Sync {
     fn render(&self) -> Result<RSX, Error> { Ok(RSX::None) }
 }
 
+/// Handles receiving props from the reconciler. This is called before every `render()`, with
+/// the props that were passed in the `rsx! {}` tree. You'll generally want to `derive(Props)`
+/// rather than implement this yourself.
 pub trait Props {
-    fn set_props(&mut self, new_props: &mut Any);
+    fn set_props(&mut self, new_props: &Any);
+
+    /// Used for memoization: if this returns `true` (and styles and children are unchanged), the
+    /// reconciler skips re-rendering this component. Returns `false` by default; use
+    /// `#[props(memo)]` with `derive(Props)` to have this compare your props with `PartialEq`.
+    ///
+    /// Children are only unchanged if they're text, or the very same nodes as last time (e.g,
+    /// passed down from a parent's own children); a node freshly made by `rsx! {}` never is. So
+    /// memoization is really for components without children of their own.
+    fn props_are_equal(&self, _old_props: &Any, _new_props: &Any) -> bool { false }
 }
 
 /// The `Component` lifecycle, mostly inspired from React, with a few extra methods for views that
@@ -83,7 +110,7 @@ pub trait Props {
 /// Alchemy does not currently implement Hooks, and at the moment has no plans to do so (the API
 /// doesn't feel comfortable in Rust, in any way I tried). If you think you have an interesting
 /// proposal for this, feel free to open an issue!
-pub trait Component: Props + Send + Sync {
+pub trait Component: Props + AsAny + Send + Sync {
     fn new(key: ComponentKey) -> Self where Self: Sized;
 
     /// Indicates whether a Component instance carries a native backing node. If you return `true`
@@ -99,17 +126,70 @@ pub trait Component: Props + Send + Sync {
     fn append_child_node(&self, _component: PlatformSpecificNodeType) {}
 
     /// If you implement a Native-backed component, you'll need to implement this. Given a
-    /// `node`, you need to instruct the system how to replace it in the tree at your point.
-    fn replace_child_node(&self, _component: PlatformSpecificNodeType) {}
+    /// `node`, you need to instruct the system how to insert it at `index` among your children,
+    /// moving it if it's already one of them. This is how the reconciler keeps the backing tree
+    /// in the same order as the `Component` tree. Defaults to `append_child_node()`.
+    fn insert_child_node_at(&self, component: PlatformSpecificNodeType, _index: usize) {
+        self.append_child_node(component);
+    }
+
+    /// If you implement a Native-backed component, you'll need to implement this. Given an
+    /// `old` node and a `new` one, you need to instruct the system how to swap them in the tree
+    /// at your point, keeping the position of `old`.
+    fn replace_child_node(&self, _old: PlatformSpecificNodeType, _new: PlatformSpecificNodeType) {}
 
     /// If you implement a Native-backed component, you'll need to implement this. Given a
     /// `node`, you need to instruct the system how to remove it from the tree at your point.
     fn remove_child_node(&self, _component: PlatformSpecificNodeType) {}
 
+    /// If this returns a root `ComponentKey` (as in, one registered with the `RenderEngine`, like
+    /// a `Window`'s), this `Component`'s children are laid out and attached under that root rather
+    /// than under this `Component`'s own native ancestor. They're still descendants of this
+    /// `Component` for everything else (context, error boundaries, and so on). You'll generally
+    /// want to use the `Portal` component rather than implement this yourself.
+    fn portal_target(&self) -> Option<ComponentKey> { None }
+
+    /// Indicates whether this `Component` can take keyboard focus (e.g, a text field). Focusable
+    /// `Component`s are focused when clicked, and are reachable with Tab/Shift-Tab in tree
+    /// order. A `tabIndex` attribute in `rsx! {}` overrides this. Returns `false` by default.
+    fn accepts_focus(&self) -> bool { false }
+
+    /// Return properties here to expose this `Component` to assistive technology (e.g,
+    /// VoiceOver). `Component`s that return `None` are left out of the accessibility tree, with
+    /// their children taking their place. Returns `None` by default.
+    fn accessibility_properties(&self) -> Option<AccessibilityProperties> { None }
+
+    /// If you implement a Native-backed component whose size depends on its content (e.g, text),
+    /// return a function here that measures it, given the space available. This is called with
+    /// the configured `appearance` (for fonts and so on) when the `Component` mounts, and again
+    /// after any render that changes its text or font, and is used for sizing in place of
+    /// `width`/`height` styles that aren't set.
+    fn measure_func(&self, _appearance: &Appearance) -> Option<MeasureFunc> { None }
+
     /// Given a configured 'appearance' and computed `layout`, this method should transform them 
     /// into appropriate calls to the backing native node.
     fn apply_styles(&self, _appearance: &Appearance, _layout: &Layout) {}
 
+    /// If you implement a Native-backed component that displays the text passed to it as
+    /// children (e.g, a label), this is called with that text whenever it changes, for you to
+    /// hand to the backing native node.
+    fn apply_text(&self, _text: &str) {}
+
+    /// If you implement a Native-backed component that describes itself to assistive
+    /// technology, this is called with its `accessibility_properties()` whenever they change,
+    /// for you to hand to the backing native node.
+    fn apply_accessibility(&self, _properties: &AccessibilityProperties) {}
+
+    /// Return a value here to make it available to every descendant through `Context`. This is called
+    /// right before `render()`; if the value changes (as in, a different `Arc`), descendants that read
+    /// the old one are re-rendered, so hold on to the `Arc` rather than building a new one every time.
+    fn provide_context(&self) -> Option<Arc<Any + Send + Sync>> { None }
+
+    /// Invoked before rendering, both on the initial mount and on subsequent updates, with the values
+    /// provided by this component's ancestors. Reading a value through `context.get()` subscribes this
+    /// component to it; it'll be re-rendered when it changes, even if it'd otherwise be skipped.
+    fn receive_context(&mut self, _context: &Context) {}
+
     /// Invoked right before calling the render method, both on the initial mount and on subsequent updates.
     /// It should return an object to update the state, or null to update nothing.
     /// This method exists for rare use cases where the state depends on changes in props over time.
@@ -122,7 +202,9 @@ pub trait Component: Props + Send + Sync {
     /// 
     /// This use case is not common, but it may occur in UIs like a chat thread that need to handle scroll 
     /// position in a special way. A snapshot value (or None) should be returned.
-    fn get_snapshot_before_update(&self) {}
+    ///
+    /// `prev_props` are the props from before this update; you can downcast them to your props type.
+    fn get_snapshot_before_update(&self, _prev_props: &Any) -> Option<Box<Any + Send>> { None }
 
     /// Invoked immediately after a component is mounted (inserted into the tree).
     /// If you need to load data from a remote endpoint, this is a good place to instantiate the network request.
@@ -133,7 +215,10 @@ pub trait Component: Props + Send + Sync {
     /// Invoked immediately after updating occurs. This method is not called for the initial render.
     /// This is also a good place to do network requests as long as you compare the current props to previous props 
     /// (e.g. a network request may not be necessary if the props have not changed).
-    fn component_did_update(&mut self) {}
+    ///
+    /// This is called once the update has been committed to the backing layer tree (including layout and styles),
+    /// children first. `snapshot` is whatever `get_snapshot_before_update()` returned.
+    fn component_did_update(&mut self, _prev_props: &Any, _snapshot: Option<Box<Any + Send>>) {}
 
     /// Invoked immediately before a component is unmounted and destroyed. Perform any necessary cleanup in this 
     /// method, such as invalidating timers, canceling network requests, or cleaning up any subscriptions that 
@@ -143,10 +228,21 @@ pub trait Component: Props + Send + Sync {
     /// component instance is unmounted, it will never be mounted again.
     fn component_will_unmount(&mut self) {}
 
+    /// Invoked when this component takes keyboard focus. Keyboard events are routed here first
+    /// until it loses focus again.
+    fn component_did_focus(&mut self) {}
+
+    /// Invoked when this component loses keyboard focus, whether to another component or because
+    /// focus was cleared.
+    fn component_did_blur(&mut self) {}
+
     /// Invoked after an error has been thrown by a descendant component. Called during the "commit" phase, 
     /// so side-effects are permitted. It should be used for things like logging errors (e.g,
     /// Sentry).
-    fn component_did_catch(&mut self /* error: */) {}
+    ///
+    /// This is called once the fallback UI (from `get_derived_state_from_error()`) has been mounted.
+    /// `info` contains the component stack, from the root down to the component that failed.
+    fn component_did_catch(&mut self, _error: &Error, _info: &ErrorInfo) {}
 
     /// Use this to let Alchemy know if a component’s output is not affected by the current change in state 
     /// or props. The default behavior is to re-render on every state change, and in the vast majority of 
@@ -169,14 +265,19 @@ pub trait Component: Props + Send + Sync {
     /// lifecycle methods instead. Keeping `render()` pure makes components easier to think about.
     ///
     /// This method is not called if should_component_update() returns `false`.
-    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> { Ok(RSX::None) }
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> { Ok(RSX::None) }
 
     /// This lifecycle is invoked after an error has been thrown by a descendant component. It receives 
-    /// the error that was thrown as a parameter and should return a value to update state.
+    /// the error that was thrown as a parameter and should update state so that the next `render()`
+    /// shows fallback UI. Errors include both `Err` returns and panics from `render()`.
+    ///
+    /// Return `true` to act as an error boundary for this error: whatever was rendered beneath this
+    /// component is torn down, and it's rendered again. Returning `false` (the default) passes the
+    /// error further up the tree; if nothing handles it, it's returned from the render.
     ///
     /// This is called during the "render" phase, so side-effects are not permitted. 
     /// For those use cases, use component_did_catch() instead.
-    fn get_derived_state_from_error(&self, _error: ()) {}
+    fn get_derived_state_from_error(&mut self, _error: &Error) -> bool { false }
 
     /// By default, when your component’s state or props change, your component will re-render. 
     /// If your `render()` method depends on some other data, you can tell Alchemy that the component 
@@ -189,5 +290,10 @@ pub trait Component: Props + Send + Sync {
     ///
     /// Normally, you should try to avoid all uses of `force_update()` and only read from `this.props` 
     /// and `this.state` in `render()`.
-    fn force_update(&self) {}
+    ///
+    /// Pass the `ComponentKey` this component was created with. If this is called while the tree
+    /// is busy (e.g, from within another lifecycle method), the re-render is queued instead.
+    fn force_update(&self, key: ComponentKey) -> Result<(), Error> {
+        RENDER_ENGINE.force_update(key)
+    }
 }
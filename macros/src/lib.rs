@@ -25,7 +25,7 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, TokenStream as TokenStream2, Literal, Span};
 use proc_macro_hack::proc_macro_hack;
 use quote::quote;
-use syn::{DeriveInput, parse_macro_input};
+use syn::{Data, DeriveInput, Meta, NestedMeta, parse_macro_input};
 
 use alchemy_styles::cssparser::{Parser, ParserInput, RuleListParser};
 use alchemy_styles::styles_parser::{Rule, RuleParser};
@@ -80,7 +80,16 @@ pub fn styles(input: TokenStream) -> TokenStream {
 }
 
 /// Implements a derive macro for automating props setting and conversion.
-#[proc_macro_derive(Props)]
+///
+/// If the struct has a field named `props`, incoming props are cloned into it on every
+/// render pass (so your `{Name}Props` type should implement `Clone`).
+///
+/// Adding `#[props(memo)]` opts the component in to memoization: if `{Name}Props` (which must
+/// then implement `PartialEq`), styles and children are unchanged between renders, the
+/// reconciler skips re-rendering it (and everything beneath it). Children made anew by `rsx! {}`
+/// always count as changed, so this only pays off for components without any (or with text, or
+/// children passed down as-is).
+#[proc_macro_derive(Props, attributes(props))]
 pub fn writable_props_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -88,6 +97,43 @@ pub fn writable_props_derive(input: TokenStream) -> TokenStream {
     let generics = input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let has_props_field = match &input.data {
+        Data::Struct(data) => data.fields.iter().any(|field| match &field.ident {
+            Some(ident) => ident == "props",
+            None => false
+        }),
+        _ => false
+    };
+
+    let is_memo = input.attrs.iter().filter(|attr| attr.path.is_ident("props")).any(|attr| {
+        match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(Meta::Word(word)) => word == "memo",
+                _ => false
+            }),
+            _ => false
+        }
+    });
+
+    let props_are_equal = if is_memo {
+        quote! {
+            fn props_are_equal(&self, old_props: &std::any::Any, new_props: &std::any::Any) -> bool {
+                match (old_props.downcast_ref::<#name_props>(), new_props.downcast_ref::<#name_props>()) {
+                    (Some(old_props), Some(new_props)) => old_props == new_props,
+                    _ => false
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let persist_props = if has_props_field {
+        quote!(self.props = props.clone();)
+    } else {
+        quote!(let _ = props;)
+    };
+
     TokenStream::from(quote! {
         impl #impl_generics #name #ty_generics #where_clause {
             pub fn default_props() -> #name_props {
@@ -96,12 +142,14 @@ pub fn writable_props_derive(input: TokenStream) -> TokenStream {
         }
 
         impl #impl_generics alchemy::ComponentProps for #name #ty_generics #where_clause {
-            fn set_props(&mut self, new_props: &mut std::any::Any) {
+            fn set_props(&mut self, new_props: &std::any::Any) {
                 match new_props.downcast_ref::<#name_props>() {
-                    Some(props) => { },
+                    Some(props) => { #persist_props },
                     None => { panic!("Woah there, somehow the wrong props were being passed!"); }
                 }
             }
+
+            #props_are_equal
         }
     })
 }
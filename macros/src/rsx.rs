@@ -1,4 +1,4 @@
-use proc_macro2::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Literal, TokenStream, TokenTree};
 use quote::{quote, quote_spanned};
 
 use crate::error::ParseError;
@@ -68,6 +68,8 @@ pub struct Element {
     pub children: Vec<Node>,
 }
 
+/// Pulls event handler attributes (`onClick`, `onKeyDown`, etc) out of `attrs`, keyed by the
+/// name of the `RSX` method that sets them (`on_click`, `on_key_down`, etc).
 fn extract_event_handlers(
     attrs: &mut StringyMap<Ident, TokenTree>,
 ) -> StringyMap<Ident, TokenTree> {
@@ -76,10 +78,21 @@ fn extract_event_handlers(
     for key in keys {
         let key_name = key.to_string();
         let prefix = "on";
-        if key_name.starts_with(prefix) {
-            let event_name = &key_name[prefix.len()..];
+        let is_event = key_name.starts_with(prefix)
+            && key_name[prefix.len()..].starts_with(|c: char| c.is_ascii_uppercase());
+
+        if is_event {
+            let mut method_name = prefix.to_string();
+            for c in key_name[prefix.len()..].chars() {
+                if c.is_ascii_uppercase() {
+                    method_name.push('_');
+                }
+
+                method_name.push(c.to_ascii_lowercase());
+            }
+
             let value = attrs.remove(&key).unwrap();
-            events.insert(ident::new_raw(event_name, key.span()), value);
+            events.insert(Ident::new(&method_name, key.span()), value);
         }
     }
     events
@@ -130,8 +143,44 @@ impl Element {
         let mut attributes = TokenStream::new();
         let mut styles = TokenStream::new();
         styles.extend(quote!(alchemy::SpacedSet::new()));
+        let mut node_key = TokenStream::new();
+        let mut node_ref = TokenStream::new();
+        let mut tab_index = TokenStream::new();
 
         for (attr_str, key, value) in attrs {
+            // `key` isn't a prop; it's used by the reconciler to match children across renders,
+            // so it's pulled off here and set on the node itself.
+            if attr_str == "key" {
+                let value = process_value(value);
+                node_key = quote!(.with_key(#value));
+                continue;
+            }
+
+            // Same goes for `ref`, which the reconciler attaches to the mounted `Component`. This
+            // is borrowed rather than moved (`ref={self.input}` shouldn't move out of `self`), so
+            // braces are unwrapped to keep them from making a temporary.
+            if attr_str == "ref" {
+                let value = match value {
+                    TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => {
+                        let content = g.stream();
+                        quote!( ( #content ) )
+                    },
+
+                    value => process_value(value)
+                };
+
+                node_ref = quote!(.with_ref(&#value));
+                continue;
+            }
+
+            // `tabIndex` decides how the node takes keyboard focus, which is also the reconciler's
+            // business rather than the `Component`'s.
+            if attr_str == "tabIndex" {
+                let value = process_value(value);
+                tab_index = quote!(.with_tab_index(#value));
+                continue;
+            }
+
             match value {
                 TokenTree::Literal(lit) if is_string_literal(lit) => {
                     let mut eprintln_msg = "ERROR: ".to_owned();
@@ -175,10 +224,6 @@ impl Element {
                         continue;
                     }
 
-                    if prop == "r#key" {
-                        continue;
-                    }
-
                     attributes.extend(quote!(
                         props.#key = std::convert::Into::into(#value);
                     ));
@@ -186,22 +231,12 @@ impl Element {
             }
         }
         
-        for (key, _value) in events.iter() {
-            if ty.is_none() {
-                let mut err = quote_spanned! { key.span() =>
-                    compile_error! { "when using event handlers, you must declare the output type inside the rsx! macro" }
-                };
-                let hint = quote_spanned! { Span::call_site() =>
-                    compile_error! { "for example: change rsx!(<div>...</div>) to rsx!(<div>...</div> : String)" }
-                };
-                err.extend(hint);
-                return Err(err);
-            }
-            //let key = TokenTree::Ident(key.clone());
-            //let value = process_value(value);
-            /*body.extend(quote!(
-                element.events.#key = Some(alchemy::dom::events::IntoEventHandler::into_event_handler(#value));
-            ));*/
+        // Each handler is set through a typed method on `RSX`, so a closure with the wrong
+        // signature (or an event that doesn't exist) fails to compile.
+        let mut event_handlers = TokenStream::new();
+        for (key, value) in events.iter() {
+            let value = process_value(value);
+            event_handlers.extend(quote!(.#key(#value)));
         }
 
         /*let mut args = TokenStream::new();
@@ -222,14 +257,15 @@ impl Element {
             alchemy::RSX::node(#component_name, #styles, |key| {
                 Box::new(<#typename as alchemy::Component>::new(key))
             }, {
-                let props = #typename::default_props();
+                #[allow(unused_mut)]
+                let mut props = #typename::default_props();
                 #attributes
-                Box::new(props)
+                props
             }, {
                 let mut children = vec![];
                 #children
                 children
-            })
+            })#node_key#node_ref#tab_index#event_handlers
         })
     }
 }
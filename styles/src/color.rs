@@ -8,8 +8,10 @@ use std::{fmt, f32::consts::PI};
 #[cfg(feature="parser")]
 use cssparser::{BasicParseError, ParseError, Parser, ToCss, Token};
 
+use serde::{Deserialize, Serialize};
+
 /// A color with red, green, blue, and alpha components, in a byte each.
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Color {
     /// The red component.
     pub red: u8,
@@ -3,10 +3,12 @@
 
 use core::ops::Add;
 
+use serde::{Deserialize, Serialize};
+
 use crate::stretch::number::Number;
 use crate::stretch::style;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
 pub struct Rect<T> {
     pub start: T,
     pub end: T,
@@ -83,7 +85,7 @@ where
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Size<T> {
     pub width: T,
     pub height: T,
@@ -132,7 +134,7 @@ impl<T> Size<T> {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
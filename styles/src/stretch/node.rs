@@ -16,7 +16,8 @@ use crate::stretch::result::{Cache, Layout};
 use crate::stretch::style::*;
 use crate::stretch::Error;
 
-type MeasureFunc = Box<Fn(Size<Number>) -> Result<Size<f32>, Box<Any>> + Send + Sync + 'static>;
+/// Given the space available to a leaf node, returns the size of its content.
+pub type MeasureFunc = Box<Fn(Size<Number>) -> Result<Size<f32>, Box<Any>> + Send + Sync + 'static>;
 
 lazy_static! {
     /// Global stretch instance id allocator.
@@ -3,7 +3,9 @@
 
 use core::ops;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+use serde::Serialize;
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Number {
     Defined(f32),
     Undefined,
@@ -1,11 +1,13 @@
 //! This module is included while awaiting an upstream merge in stretch proper.
 //! You should not rely on it, and consider it an implementation detail.
 
+use serde::{Deserialize, Serialize};
+
 use crate::stretch::algo::ComputeResult;
 use crate::stretch::geometry::{Point, Size};
 use crate::stretch::number::Number;
 
-#[derive(Copy, Debug, Clone)]
+#[derive(Copy, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Layout {
     pub(crate) order: u32,
     pub size: Size<f32>,
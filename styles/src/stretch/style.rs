@@ -1,10 +1,12 @@
 //! This module is included while awaiting an upstream merge in stretch proper.
 //! You should not rely on it, and consider it an implementation detail.
 
+use serde::Serialize;
+
 use crate::stretch::geometry::{Rect, Size};
 use crate::stretch::number::Number;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum AlignItems {
     FlexStart,
     FlexEnd,
@@ -19,7 +21,7 @@ impl Default for AlignItems {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum AlignSelf {
     Auto,
     FlexStart,
@@ -35,7 +37,7 @@ impl Default for AlignSelf {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum AlignContent {
     FlexStart,
     FlexEnd,
@@ -51,7 +53,7 @@ impl Default for AlignContent {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Direction {
     Inherit,
     LTR,
@@ -64,7 +66,7 @@ impl Default for Direction {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Display {
     Flex,
     None,
@@ -76,7 +78,7 @@ impl Default for Display {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum FlexDirection {
     Row,
     Column,
@@ -104,7 +106,7 @@ impl FlexDirection {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum JustifyContent {
     FlexStart,
     FlexEnd,
@@ -120,7 +122,7 @@ impl Default for JustifyContent {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Overflow {
     Visible,
     Hidden,
@@ -133,7 +135,7 @@ impl Default for Overflow {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum PositionType {
     Relative,
     Absolute,
@@ -145,7 +147,7 @@ impl Default for PositionType {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum FlexWrap {
     NoWrap,
     Wrap,
@@ -158,7 +160,7 @@ impl Default for FlexWrap {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub enum Dimension {
     Undefined,
     Auto,
@@ -202,7 +204,7 @@ impl Default for Size<Dimension> {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
 pub struct Style {
     pub display: Display,
     pub position_type: PositionType,
@@ -7,9 +7,12 @@ use proc_macro2::{TokenStream, Ident, Span};
 #[cfg(feature="tokenize")]
 use quote::{quote, ToTokens};
 
+use serde::{Deserialize, Serialize};
+
 pub use crate::color::Color;
 
 pub use crate::stretch::geometry::{Point, Rect, Size};
+pub use crate::stretch::node::MeasureFunc;
 pub use crate::stretch::number::Number;
 pub use crate::stretch::result::Layout;
 
@@ -33,7 +36,7 @@ impl Default for BackfaceVisibility {
 }
 
 /// Describes a font style.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum FontStyle {
     Normal,
     Italic,
@@ -47,7 +50,7 @@ impl Default for FontStyle {
 }
 
 /// Describes a font weight.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum FontWeight {
     Normal,
     Bold
@@ -60,7 +63,7 @@ impl Default for FontWeight {
 }
 
 /// Describes how text should be aligned.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TextAlignment {
     Auto,
     Left,
@@ -104,6 +107,7 @@ impl Default for FontFamily {
 /// When applying layout to a backing view, you'll get two calls - one with a `Layout`, 
 /// which contains the computed frame, and one with an `Appearance`, which contains things 
 /// like colors, fonts, and so on.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Appearance {
     pub background_color: Color,
     pub font_size: f32,